@@ -52,7 +52,7 @@ NullPolicy = Literal[
     "drop_y_zero_x",  # only drops rows with null targets and fill any null features with zero
 ]
 OutputMode = Literal["predictions", "residuals", "coefficients"]
-SolveMethod = Literal["qr", "svd", "chol", "lu", "cd"]
+SolveMethod = Literal["qr", "svd", "chol", "lu", "cd", "cg", "lsqr"]
 
 _VALID_NULL_POLICIES: Set[NullPolicy] = set(get_args(NullPolicy))
 _VALID_OUTPUT_MODES: Set[OutputMode] = set(get_args(OutputMode))
@@ -78,6 +78,9 @@ class OLSKwargs:
         rcond: Optional float specifying cut-off ratio for small singular values. Only relevant for
                "SVD" solve methods. Defaults to None, where it is chosen as per
                 numpy lstsq convention.
+        standardize: Whether to scale features to unit variance before coordinate descent
+            (coefficients are returned in original units). Only relevant for ElasticNet / LASSO.
+            Defaults to False.
     """
 
     alpha: Optional[float] = 0.0
@@ -88,6 +91,7 @@ class OLSKwargs:
     null_policy: NullPolicy = "ignore"
     solve_method: Optional[SolveMethod] = None
     rcond: Optional[float] = None
+    standardize: Optional[bool] = False
 
     def to_dict(self) -> Dict[str, Any]:
         return asdict(self)
@@ -136,6 +140,15 @@ class RollingKwargs:
         use_woodbury: Whether to use Woodbury matrix identity for faster computation.
                       Defaults to True if num_features > 10.
         alpha: L2 Regularization strength. Default is 0.0.
+        center: Whether to center the window on each row (using window_size // 2 rows on each
+                side, shrinking near the boundaries). Intended for smoothing, as it looks ahead.
+                Default is False.
+        report_deltas: Whether to report coefficients as changes relative to the first (warm-up)
+                       fit, e.g. to monitor coefficient drift. Only applies to coefficients, not
+                       predictions. Default is False.
+        temporal_alpha: Penalty strength on the change of each window's coefficients from the
+                        previous window's, for smoother coefficient paths. Requires trailing
+                        (non-centered) windows. Default is None (independent window fits).
         null_policy: Strategy for handling missing data. Defaults to "ignore".
     """
 
@@ -143,6 +156,9 @@ class RollingKwargs:
     min_periods: Optional[int] = None
     use_woodbury: Optional[bool] = None
     alpha: Optional[float] = None  # optional ridge alpha
+    center: bool = False
+    report_deltas: bool = False
+    temporal_alpha: Optional[float] = None
     null_policy: NullPolicy = "ignore"
 
     def to_dict(self) -> Dict[str, Any]:
@@ -198,10 +198,10 @@ fn compute_is_valid_mask(inputs: &[Series], null_policy: &NullPolicy) -> Option<
 /// * `inputs` - A slice of input series to be processed.
 /// * `null_policy` - The null handling policy to be applied.
 /// * `is_valid_mask` - A boolean array which specifies, based on the chosen null policy,
-///                     which row samples are valid.
+///   which row samples are valid.
 /// * `outputs` - A mutable reference to a vector of series where null values have been handled
-///               according to the specified policy. If no null handling is required
-///               (NullPolicy::Ignore), `outputs` will contain a reference to the original `inputs`
+///   according to the specified policy. If no null handling is required
+///   (NullPolicy::Ignore), `outputs` will contain a reference to the original `inputs`
 fn handle_nulls(
     inputs: &[Series],
     null_policy: &NullPolicy,
@@ -253,6 +253,7 @@ pub struct OLSKwargs {
     solve_method: Option<String>,
     null_policy: Option<String>,
     rcond: Option<f64>,
+    standardize: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -269,6 +270,9 @@ pub struct RollingKwargs {
     min_periods: Option<usize>,
     use_woodbury: Option<bool>,
     alpha: Option<f64>,
+    center: Option<bool>,
+    report_deltas: Option<bool>,
+    temporal_alpha: Option<f64>,
     null_policy: Option<String>,
 }
 
@@ -315,7 +319,10 @@ fn _get_least_squares_coefficients(
         && !positive
         && matches!(
             solve_method,
-            None | Some(SolveMethod::SVD) | Some(SolveMethod::QR)
+            None | Some(SolveMethod::SVD)
+                | Some(SolveMethod::QR)
+                | Some(SolveMethod::CG)
+                | Some(SolveMethod::LSQR)
         )
     {
         solve_ols(targets, features, solve_method, kwargs.rcond)
@@ -331,6 +338,8 @@ fn _get_least_squares_coefficients(
             kwargs.tol,
             kwargs.positive,
             solve_method,
+            None,
+            kwargs.standardize,
         )
     }
 }
@@ -462,6 +471,9 @@ fn rolling_least_squares_coefficients(
         kwargs.min_periods,
         kwargs.use_woodbury,
         kwargs.alpha,
+        kwargs.center,
+        kwargs.report_deltas,
+        kwargs.temporal_alpha,
     );
     let series = coefficients_to_struct_series(&coefficients);
     Ok(series.with_name("coefficients"))
@@ -482,6 +494,9 @@ fn rolling_least_squares(inputs: &[Series], kwargs: RollingKwargs) -> PolarsResu
         kwargs.min_periods,
         kwargs.use_woodbury,
         kwargs.alpha,
+        kwargs.center,
+        None, // predictions require the absolute coefficients
+        kwargs.temporal_alpha,
     );
     let predictions = (&x * &coefficients).sum_axis(Axis(1));
     Ok(Series::from_vec(inputs[0].name(), predictions.to_vec()))
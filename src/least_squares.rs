@@ -2,15 +2,63 @@ use faer::linalg::solvers::SolverCore;
 use faer::prelude::*;
 use faer::Side;
 use faer_ext::{IntoFaer, IntoNdarray};
-use ndarray::{array, s, Array, Array1, Array2, ArrayView1, Axis, NewAxis};
+use ndarray::{array, s, Array, Array1, Array2, Array3, ArrayView1, Axis, CowArray, NewAxis};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::{Rng, SeedableRng};
 use std::cmp::max;
 use std::str::FromStr;
 
 #[cfg(any(target_os = "macos", target_os = "linux"))]
 use ndarray_linalg::LeastSquaresSvd;
 
+/// Floating point types supported by the generic solvers ([`solve_ols_generic`],
+/// [`solve_ridge_generic`] and [`solve_elastic_net_generic`]): `f32` and `f64`. Using `f32`
+/// halves the memory of large feature matrices at the cost of precision.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub trait Float:
+    faer::RealField
+    + faer::SimpleEntity
+    + num_traits::Float
+    + num_traits::FromPrimitive
+    + ndarray::LinalgScalar
+    + ndarray::ScalarOperand
+    + ndarray_linalg::Lapack
+{
+}
+
+/// Floating point types supported by the generic solvers ([`solve_ols_generic`],
+/// [`solve_ridge_generic`] and [`solve_elastic_net_generic`]): `f32` and `f64`. Using `f32`
+/// halves the memory of large feature matrices at the cost of precision.
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub trait Float:
+    faer::RealField
+    + faer::SimpleEntity
+    + num_traits::Float
+    + num_traits::FromPrimitive
+    + ndarray::LinalgScalar
+    + ndarray::ScalarOperand
+{
+}
+
+impl Float for f32 {}
+impl Float for f64 {}
+
 /// Invert square matrix input using either Cholesky or LU decomposition
-pub fn inv(array: &Array2<f64>, use_cholesky: bool) -> Array2<f64> {
+///
+/// An optional `ridge_floor` eps adds eps * trace(A) / k to the diagonal before inverting,
+/// which guarantees a finite (regularized) inverse of singular or near-singular Gram matrices.
+pub fn inv(array: &Array2<f64>, use_cholesky: bool, ridge_floor: Option<f64>) -> Array2<f64> {
+    let floored;
+    let array = match ridge_floor {
+        Some(eps) => {
+            assert!(eps >= 0., "ridge_floor must be non-negative");
+            let k = array.shape()[0];
+            let floor = eps * array.diag().sum() / k as f64;
+            floored = array + &(Array2::<f64>::eye(k) * floor);
+            &floored
+        }
+        None => array,
+    };
     let m = array.view().into_faer();
     if use_cholesky {
         match m.cholesky(Side::Lower) {
@@ -36,7 +84,9 @@ pub enum SolveMethod {
     SVD,
     Cholesky,
     LU,
-    CD, // coordinate-descent for elastic net problem
+    CD,   // coordinate-descent for elastic net problem
+    CG,   // conjugate gradients on the normal equations (matrix-free)
+    LSQR, // Paige-Saunders LSQR iterations on X (matrix-free)
 }
 
 impl FromStr for SolveMethod {
@@ -49,6 +99,8 @@ impl FromStr for SolveMethod {
             "chol" => Ok(SolveMethod::Cholesky),
             "lu" => Ok(SolveMethod::LU),
             "cd" => Ok(SolveMethod::CD),
+            "cg" => Ok(SolveMethod::CG),
+            "lsqr" => Ok(SolveMethod::LSQR),
             _ => Err(()),
         }
     }
@@ -66,12 +118,12 @@ impl FromStr for SolveMethod {
 /// # Returns
 ///
 /// * Result of ridge regression as a 1-dimensional array.
-fn solve_ridge_svd(
-    y: &Array1<f64>,
-    x: &Array2<f64>,
-    alpha: f64,
-    rcond: Option<f64>,
-) -> Array1<f64> {
+fn solve_ridge_svd<T: Float>(
+    y: &Array1<T>,
+    x: &Array2<T>,
+    alpha: T,
+    rcond: Option<T>,
+) -> Array1<T> {
     let x_faer = x.view().into_faer();
     let y_faer = y.view().insert_axis(Axis(1)).into_faer();
 
@@ -82,35 +134,42 @@ fn solve_ridge_svd(
     let s = svd.s_diagonal();
 
     // convert s into ndarray
-    let s: Array1<f64> = s.as_2d().into_ndarray().slice(s![.., 0]).into_owned();
-    let max_value = s.iter().skip(1).copied().fold(s[0], f64::max);
+    let s: Array1<T> = s.as_2d().into_ndarray().slice(s![.., 0]).into_owned();
+    let max_value = s.iter().skip(1).copied().fold(s[0], num_traits::Float::max);
 
     // set singular values less than or equal to ``rcond * largest_singular_value`` to zero.
-    let cutoff =
-        rcond.unwrap_or(f64::EPSILON * max(x_faer.ncols(), x_faer.nrows()) as f64) * max_value;
-    let s = s.map(|v| if v < &cutoff { 0. } else { *v });
+    let n_max = T::from_usize(max(x_faer.ncols(), x_faer.nrows())).unwrap();
+    let cutoff = rcond.unwrap_or(<T as num_traits::Float>::epsilon() * n_max) * max_value;
+    let s = s.map(|v| if v < &cutoff { T::zero() } else { *v });
 
     let binding = u.transpose() * y_faer;
-    let u_t_y: Array1<f64> = binding
+    let u_t_y: Array1<T> = binding
         .as_ref()
         .into_ndarray()
         .slice(s![.., 0])
         .into_owned();
-    let d = &s / (&s * &s + alpha);
+    // zeroed singular values contribute nothing (also without a ridge penalty)
+    let d = s.mapv(|s| {
+        if s > T::zero() {
+            s / (s * s + alpha)
+        } else {
+            T::zero()
+        }
+    });
     let d_ut_y = &d * &u_t_y;
     v.dot(&d_ut_y)
 }
 
 #[cfg(not(any(target_os = "macos", target_os = "linux")))]
-fn solve_ols_svd(y: &Array1<f64>, x: &Array2<f64>, rcond: Option<f64>) -> Array1<f64> {
+fn solve_ols_svd<T: Float>(y: &Array1<T>, x: &Array2<T>, rcond: Option<T>) -> Array1<T> {
     // TODO: try to compute w/ LAPACK SVD. Must handle BLAS dependency on linux & windows OS
     //      either use ndarray-linalg or directly call sgelsd from lapack crate..
-    solve_ridge_svd(y, x, 1.0e-64, rcond) // near zero ridge penalty
+    solve_ridge_svd(y, x, T::from_f64(1.0e-64).unwrap(), rcond) // near zero ridge penalty
 }
 
 #[cfg(any(target_os = "macos", target_os = "linux"))]
 #[allow(unused_variables)]
-fn solve_ols_svd(y: &Array1<f64>, x: &Array2<f64>, rcond: Option<f64>) -> Array1<f64> {
+fn solve_ols_svd<T: Float>(y: &Array1<T>, x: &Array2<T>, rcond: Option<T>) -> Array1<T> {
     x.least_squares(y)
         .expect("Failed to compute LAPACK SVD solution!")
         .solution
@@ -125,12 +184,24 @@ pub fn solve_ols(
     solve_method: Option<SolveMethod>,
     rcond: Option<f64>,
 ) -> Array1<f64> {
+    solve_ols_generic(y, x, solve_method, rcond)
+}
+
+/// Solves an ordinary least squares problem as [`solve_ols`], for any [`Float`] type.
+pub fn solve_ols_generic<T: Float>(
+    y: &Array1<T>,
+    x: &Array2<T>,
+    solve_method: Option<SolveMethod>,
+    rcond: Option<T>,
+) -> Array1<T> {
     let n_features = x.len_of(Axis(1));
     let n_samples = x.len_of(Axis(0));
 
     let solve_method = match solve_method {
         Some(SolveMethod::QR) => SolveMethod::QR,
         Some(SolveMethod::SVD) => SolveMethod::SVD,
+        Some(SolveMethod::CG) => return solve_ols_cg(y, x, None, None),
+        Some(SolveMethod::LSQR) => return solve_ols_lsqr(y, x, None, None),
         None => {
             // automatically determine recommended solution method based on shape of data
             if n_samples > n_features {
@@ -139,7 +210,9 @@ pub fn solve_ols(
                 SolveMethod::SVD
             }
         }
-        _ => panic!("Only 'QR' and 'SVD' are currently supported solve methods for OLS."),
+        _ => panic!(
+            "Only 'QR', 'SVD', 'CG' and 'LSQR' are currently supported solve methods for OLS."
+        ),
     };
 
     if solve_method == SolveMethod::QR {
@@ -157,9 +230,351 @@ pub fn solve_ols(
     }
 }
 
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn solve_ols_svd_multi(y: &Array2<f64>, x: &Array2<f64>, rcond: Option<f64>) -> Array2<f64> {
+    // pseudo-inverse from a single SVD: B = V diag(1 / s) U^T Y
+    let svd = x.view().into_faer().thin_svd();
+    let u = svd.u().into_ndarray();
+    let v = svd.v().into_ndarray();
+    let s = svd.s_diagonal();
+    let s: Array1<f64> = s.as_2d().into_ndarray().slice(s![.., 0]).into_owned();
+    let max_value = s.iter().copied().fold(0., f64::max);
+    let cutoff = rcond.unwrap_or(f64::EPSILON * max(x.nrows(), x.ncols()) as f64) * max_value;
+    let s_inv = s.mapv(|s| if s > cutoff { 1. / s } else { 0. });
+    v.dot(&(u.t().dot(y) * s_inv.view().insert_axis(Axis(1))))
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+#[allow(unused_variables)]
+fn solve_ols_svd_multi(y: &Array2<f64>, x: &Array2<f64>, rcond: Option<f64>) -> Array2<f64> {
+    x.least_squares(y)
+        .expect("Failed to compute LAPACK SVD solution!")
+        .solution
+}
+
+/// Solves ordinary least squares problems for several targets (the columns of `y`) sharing the
+/// same features, returning the (k x m) matrix of coefficients (one column per target).
+///
+/// The factorization of X (QR or SVD) or of X^T X (Cholesky or LU) is computed once and reused
+/// for all target columns, which is much cheaper than m independent calls to [`solve_ols`].
+/// Without a `solve_method`, QR is used for tall and SVD for wide problems.
+pub fn solve_ols_multi(
+    y: &Array2<f64>,
+    x: &Array2<f64>,
+    solve_method: Option<SolveMethod>,
+    rcond: Option<f64>,
+) -> Array2<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    assert_eq!(y.shape()[0], n_samples, "y must have one row per sample");
+    let solve_method = solve_method.unwrap_or(if n_samples > n_features {
+        SolveMethod::QR
+    } else {
+        SolveMethod::SVD
+    });
+    match solve_method {
+        SolveMethod::QR => x
+            .view()
+            .into_faer()
+            .col_piv_qr()
+            .solve_lstsq(y.view().into_faer())
+            .as_ref()
+            .into_ndarray()
+            .to_owned(),
+        SolveMethod::SVD => solve_ols_svd_multi(y, x, rcond),
+        SolveMethod::Cholesky | SolveMethod::LU => {
+            let xtx = x.t().dot(x);
+            let xty = x.t().dot(y);
+            let xtx_faer = xtx.view().into_faer();
+            if solve_method == SolveMethod::Cholesky {
+                match xtx_faer.cholesky(Side::Lower) {
+                    Ok(cholesky) => {
+                        return cholesky
+                            .solve(xty.view().into_faer())
+                            .as_ref()
+                            .into_ndarray()
+                            .to_owned();
+                    }
+                    Err(_) => {
+                        println!("Cholesky decomposition failed, falling back to LU decomposition");
+                    }
+                }
+            }
+            xtx_faer
+                .partial_piv_lu()
+                .solve(xty.view().into_faer())
+                .as_ref()
+                .into_ndarray()
+                .to_owned()
+        }
+        _ => panic!(
+            "Only 'QR', 'SVD', 'Cholesky' and 'LU' are currently supported solve methods for \
+            multi-target OLS."
+        ),
+    }
+}
+
+/// Solves an ordinary least squares problem by conjugate gradients (CG) on the normal equations
+/// X^T X B = X^T y, using matrix-free products X^T (X v) so that X^T X is never formed.
+///
+/// This is attractive for wide, well-conditioned problems: each iteration costs two
+/// matrix-vector products, and in exact arithmetic CG terminates after at most k iterations.
+/// The error however shrinks roughly by a factor (cond(X) - 1) / (cond(X) + 1) per iteration,
+/// as the normal equations square the condition number of X, so ill-conditioned (e.g. highly
+/// collinear) features converge slowly and may silently stop at `max_iter` with a poor
+/// solution. Nothing is printed in that case, as this runs once per group in the expressions;
+/// check the relative residual of the normal equations where convergence matters, and prefer
+/// QR / SVD (or ridge regularization) for such features.
+///
+/// # Arguments
+///
+/// * `max_iter` - Optional maximum number of CG iterations. Defaults to 10 * k.
+/// * `tol` - Optional tolerance on the relative residual ||X^T (y - X B)|| / ||X^T y|| of the
+///   normal equations. Defaults to 1e-10.
+pub fn solve_ols_cg<T: Float>(
+    y: &Array1<T>,
+    x: &Array2<T>,
+    max_iter: Option<usize>,
+    tol: Option<T>,
+) -> Array1<T> {
+    let n_features = x.shape()[1];
+    let max_iter = max_iter.unwrap_or(10 * n_features);
+    let tol = tol.unwrap_or(T::from_f64(1e-10).unwrap());
+    conjugate_gradient(
+        |v: &Array1<T>| x.t().dot(&x.dot(v)),
+        &x.t().dot(y),
+        Array1::<T>::zeros(n_features),
+        max_iter,
+        tol,
+    )
+    .0
+}
+
+/// Solves an ordinary least squares problem with the LSQR algorithm of Paige & Saunders (1982).
+///
+/// LSQR works on X directly via Golub-Kahan bidiagonalization, using only the products X v and
+/// X^T u, so neither X^T X nor a factorization of X is formed. It is mathematically equivalent
+/// to CG on the normal equations (see [`solve_ols_cg`]) but numerically more reliable for
+/// ill-conditioned X, as it avoids squaring the condition number in finite precision. This
+/// trades exactness for speed and memory on large problems where QR is too expensive.
+///
+/// # Arguments
+///
+/// * `max_iter` - Optional maximum number of iterations. Defaults to 10 * k.
+/// * `tol` - Optional tolerance on the relative residual ||X^T (y - X B)|| / ||X^T y|| of the
+///   normal equations (as estimated by the recurrences). Defaults to 1e-10.
+pub fn solve_ols_lsqr<T: Float>(
+    y: &Array1<T>,
+    x: &Array2<T>,
+    max_iter: Option<usize>,
+    tol: Option<T>,
+) -> Array1<T> {
+    let n_features = x.shape()[1];
+    let max_iter = max_iter.unwrap_or(10 * n_features);
+    let tol = tol.unwrap_or(T::from_f64(1e-10).unwrap());
+    let norm = |a: &Array1<T>| num_traits::Float::sqrt(a.dot(a));
+    let mut coefficients = Array1::<T>::zeros(n_features);
+
+    // initialize the bidiagonalization: beta u = y, alpha v = X^T u
+    let mut beta = norm(y);
+    if beta == T::zero() {
+        return coefficients;
+    }
+    let mut u = y / beta;
+    let mut v = x.t().dot(&u);
+    let mut alpha = norm(&v);
+    if alpha == T::zero() {
+        return coefficients; // y is orthogonal to the columns of X
+    }
+    v /= alpha;
+    let threshold = tol * alpha * beta; // tol * ||X^T y||
+    let mut w = v.clone();
+    let (mut phi_bar, mut rho_bar) = (beta, alpha);
+
+    for _ in 0..max_iter {
+        // continue the bidiagonalization
+        u = x.dot(&v) - &u * alpha;
+        beta = norm(&u);
+        if beta > T::zero() {
+            u /= beta;
+        }
+        v = x.t().dot(&u) - &v * beta;
+        alpha = norm(&v);
+        if alpha > T::zero() {
+            v /= alpha;
+        }
+        // apply the next plane rotation to eliminate beta
+        let rho = num_traits::Float::sqrt(rho_bar * rho_bar + beta * beta);
+        let (c, s) = (rho_bar / rho, beta / rho);
+        let theta = s * alpha;
+        rho_bar = -c * alpha;
+        let phi = c * phi_bar;
+        phi_bar = s * phi_bar;
+        // update the solution and search direction
+        coefficients.scaled_add(phi / rho, &w);
+        w = &v - &(&w * (theta / rho));
+        // ||X^T r|| = phi_bar * alpha * |c|
+        if phi_bar * alpha * num_traits::Float::abs(c) <= threshold {
+            break;
+        }
+    }
+    coefficients
+}
+
+/// Ordinary least squares coefficients together with the fit's residuals and goodness of fit.
+#[derive(Clone, Debug)]
+pub struct OlsResult {
+    pub coef: Array1<f64>,
+    pub residuals: Array1<f64>,
+    pub r_squared: f64, // 1 - RSS / TSS, with the TSS taken around the mean of y
+    pub rss: f64,
+}
+
+/// Solves an ordinary least squares problem as [`solve_ols`], additionally returning the
+/// residuals, residual sum of squares and R^2 of the fit (see [`OlsResult`]).
+pub fn solve_ols_with_stats(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    solve_method: Option<SolveMethod>,
+    rcond: Option<f64>,
+) -> OlsResult {
+    let coef = solve_ols(y, x, solve_method, rcond);
+    let residuals = y - &x.dot(&coef);
+    let rss = residuals.dot(&residuals);
+    let deviations = y - y.mean().unwrap_or(0.);
+    let r_squared = 1. - rss / deviations.dot(&deviations);
+    OlsResult {
+        coef,
+        residuals,
+        r_squared,
+        rss,
+    }
+}
+
+/// Thin column-pivoted QR factorization of a (n x k) matrix X, with n >= k: the columns of X
+/// taken in the order of `permutation` equal Q R, i.e. X P = Q R with the permutation matrix P.
+#[derive(Clone, Debug)]
+pub struct QrDecomposition {
+    pub q: Array2<f64>,          // (n x k) with orthonormal columns
+    pub r: Array2<f64>,          // (k x k) upper triangular
+    pub permutation: Vec<usize>, // column j of Q R is column permutation[j] of X
+}
+
+/// Computes the thin column-pivoted QR factorization of X (see [`QrDecomposition`]), so that
+/// the factors can be re-used, e.g. to obtain inv(X^T X) = P inv(R) inv(R)^T P^T without
+/// forming X^T X.
+pub fn compute_qr(x: &Array2<f64>) -> QrDecomposition {
+    assert!(
+        x.shape()[0] >= x.shape()[1],
+        "thin QR requires at least as many samples as features"
+    );
+    let qr = x.view().into_faer().col_piv_qr();
+    let q = qr.compute_thin_q().as_ref().into_ndarray().to_owned();
+    let r = qr.compute_thin_r().as_ref().into_ndarray().to_owned();
+    let permutation = qr.col_permutation().arrays().0.to_vec();
+    QrDecomposition { q, r, permutation }
+}
+
+/// Applies the Givens rotation mapping (f, g) to (hypot(f, g), 0) to rows a & b of `r`, and its
+/// transpose to columns a & b of `q`, so that the product Q R is unchanged.
+fn apply_givens(q: &mut Array2<f64>, r: &mut Array2<f64>, a: usize, b: usize, f: f64, g: f64) {
+    let norm = f.hypot(g);
+    if norm == 0. {
+        return;
+    }
+    let (c, s) = (f / norm, g / norm);
+    for j in 0..r.shape()[1] {
+        let (r_a, r_b) = (r[[a, j]], r[[b, j]]);
+        r[[a, j]] = c * r_a + s * r_b;
+        r[[b, j]] = c * r_b - s * r_a;
+    }
+    for i in 0..q.shape()[0] {
+        let (q_a, q_b) = (q[[i, a]], q[[i, b]]);
+        q[[i, a]] = c * q_a + s * q_b;
+        q[[i, b]] = c * q_b - s * q_a;
+    }
+}
+
+impl QrDecomposition {
+    /// Updates the factorization after column `column` of X is replaced by `new_column`, e.g.
+    /// to refit with one feature permuted for permutation importance, in O(n k) operations.
+    ///
+    /// The change is the rank-1 update X' P = Q R + u e_p^T with u = new_column - x_column. The
+    /// part of u outside the span of Q extends the basis by one vector, after which two sweeps
+    /// of Givens rotations restore the triangular form (Golub & Van Loan, section 6.5). The
+    /// column order `permutation` is kept, i.e. the columns are not re-pivoted.
+    pub fn replace_column(&self, column: usize, new_column: &Array1<f64>) -> QrDecomposition {
+        let (n, k) = (self.q.shape()[0], self.q.shape()[1]);
+        assert_eq!(
+            new_column.len(),
+            n,
+            "the new column must have one entry per sample"
+        );
+        let p = self
+            .permutation
+            .iter()
+            .position(|&j| j == column)
+            .expect("column must be one of the factorized columns");
+        let u = new_column - &self.q.dot(&self.r.column(p));
+
+        // extended factors [Q, q_perp] & [R; 0], with the coordinates w of u in that basis
+        // carried as an extra column of R so that every rotation is applied to it as well
+        let q_t_u = self.q.t().dot(&u);
+        let orthogonal = &u - &self.q.dot(&q_t_u);
+        let mut q = Array2::<f64>::zeros((n, k + 1));
+        q.slice_mut(s![.., ..k]).assign(&self.q);
+        let mut r = Array2::<f64>::zeros((k + 1, k + 1));
+        r.slice_mut(s![..k, ..k]).assign(&self.r);
+        r.slice_mut(s![..k, k]).assign(&q_t_u);
+        let norm = orthogonal.dot(&orthogonal).sqrt();
+        if norm > f64::EPSILON * u.dot(&u).sqrt() {
+            // otherwise u lies in the span of Q
+            q.column_mut(k).assign(&(orthogonal / norm));
+            r[[k, k]] = norm;
+        }
+
+        // rotate w onto the first basis vector, which makes R upper Hessenberg
+        for i in (1..=k).rev() {
+            let (f, g) = (r[[i - 1, k]], r[[i, k]]);
+            apply_givens(&mut q, &mut r, i - 1, i, f, g);
+        }
+        r[[0, p]] += r[[0, k]];
+        // eliminate the sub-diagonal to restore the triangular form
+        for i in 0..k {
+            let (f, g) = (r[[i, i]], r[[i + 1, i]]);
+            apply_givens(&mut q, &mut r, i, i + 1, f, g);
+        }
+        QrDecomposition {
+            q: q.slice(s![.., ..k]).to_owned(),
+            r: r.slice(s![..k, ..k]).to_owned(),
+            permutation: self.permutation.clone(),
+        }
+    }
+
+    /// Solves the least squares problem min ||y - X B|| from the factors, R B_P = Q^T y by back
+    /// substitution, returning the coefficients in the original column order of X.
+    pub fn solve(&self, y: &Array1<f64>) -> Array1<f64> {
+        let k = self.r.shape()[0];
+        let q_t_y = self.q.t().dot(y);
+        let mut permuted = Array1::<f64>::zeros(k);
+        for i in (0..k).rev() {
+            let sum: f64 = (i + 1..k).map(|l| self.r[[i, l]] * permuted[l]).sum();
+            permuted[i] = (q_t_y[i] - sum) / self.r[[i, i]];
+        }
+        let mut coefficients = Array1::<f64>::zeros(k);
+        for (i, &j) in self.permutation.iter().enumerate() {
+            coefficients[j] = permuted[i];
+        }
+        coefficients
+    }
+}
+
 /// Solves the normal equations: (X^T X) coefficients = X^T Y
 /// Attempts to solve with either Cholesky or LU (partial pivoting)
-fn solve_normal_equations(xtx: &Array2<f64>, xty: &Array1<f64>, use_cholesky: bool) -> Array1<f64> {
+fn solve_normal_equations<T: Float>(
+    xtx: &Array2<T>,
+    xty: &Array1<T>,
+    use_cholesky: bool,
+) -> Array1<T> {
     // Attempt to solve via Cholesky decomposition
     let xtx_faer = xtx.view().into_faer();
     if use_cholesky {
@@ -189,16 +604,123 @@ fn solve_normal_equations(xtx: &Array2<f64>, xty: &Array1<f64>, use_cholesky: bo
         .into_owned()
 }
 
+/// Checks whether the columns of the feature matrix have very different scales.
+///
+/// Penalized solvers (ridge, elastic net) apply the same penalty to every coefficient, so on
+/// unstandardized features with very different scales the penalty is effectively uneven.
+/// Returns a warning message when the ratio of the largest to the smallest column standard
+/// deviation exceeds `max_ratio` (default: 100). Constant columns (e.g. an intercept) are ignored.
+///
+/// The solvers do not run this check themselves, as it costs a pass over X on every call; call
+/// it once up front, e.g. before fitting many folds, groups or windows on the same features.
+pub fn check_feature_scaling<T: Float>(x: &Array2<T>, max_ratio: Option<f64>) -> Option<String> {
+    let max_ratio = max_ratio.unwrap_or(100.);
+    let std_devs: Vec<(usize, f64)> = x
+        .axis_iter(Axis(1))
+        .map(|col| col.std(T::zero()).to_f64().unwrap())
+        .enumerate()
+        .filter(|(_, std)| *std > 0.)
+        .collect();
+    let (j_min, std_min) = std_devs
+        .iter()
+        .copied()
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    let (j_max, std_max) = std_devs
+        .iter()
+        .copied()
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if std_max / std_min > max_ratio {
+        Some(format!(
+            "warning: feature scales differ by a factor of {:.1} (std of column {} is {:.3e}, \
+            std of column {} is {:.3e}), so the penalty is applied unevenly. Consider \
+            standardizing features before fitting penalized models.",
+            std_max / std_min,
+            j_max,
+            std_max,
+            j_min,
+            std_min
+        ))
+    } else {
+        None
+    }
+}
+
+/// Ridge penalty of [`solve_ridge`]: either a single alpha applied uniformly to all features,
+/// or one penalty per feature (generalized Tikhonov regularization with a diagonal penalty).
+#[derive(Clone, Debug)]
+pub enum AlphaSpec {
+    Scalar(f64),
+    PerFeature(Array1<f64>),
+}
+
+impl From<f64> for AlphaSpec {
+    fn from(alpha: f64) -> Self {
+        AlphaSpec::Scalar(alpha)
+    }
+}
+
+impl From<Array1<f64>> for AlphaSpec {
+    fn from(alphas: Array1<f64>) -> Self {
+        AlphaSpec::PerFeature(alphas)
+    }
+}
+
 /// Solves a ridge regression problem of the form: ||y - x B|| + alpha * ||B||
 /// Inputs: features (2d ndarray), targets (1d ndarray), ridge alpha scalar
+///
+/// Per-feature penalties (see [`AlphaSpec`]) solve ||y - x B||^2 + sum_j alpha_j B_j^2 from the
+/// normal equations (X^T X + diag(alphas)) B = X^T y, e.g. to leave some features unpenalized
+/// (alpha_j = 0) while shrinking others hard. The SVD can not absorb non-uniform penalties, so
+/// the SVD method then falls back to LU; uniform per-feature penalties behave as a scalar.
 pub fn solve_ridge(
     y: &Array1<f64>,
     x: &Array2<f64>,
-    alpha: f64,
+    alpha: impl Into<AlphaSpec>,
     solve_method: Option<SolveMethod>,
     rcond: Option<f64>,
 ) -> Array1<f64> {
-    assert!(alpha >= 0., "alpha must be non-negative");
+    let alphas = match alpha.into() {
+        AlphaSpec::Scalar(alpha) => return solve_ridge_generic(y, x, alpha, solve_method, rcond),
+        AlphaSpec::PerFeature(alphas) => alphas,
+    };
+    assert_eq!(
+        alphas.len(),
+        x.shape()[1],
+        "one penalty per feature is required"
+    );
+    assert!(
+        alphas.iter().all(|alpha| *alpha >= 0.),
+        "alphas must be non-negative"
+    );
+    if alphas.iter().all(|alpha| *alpha == alphas[0]) && !alphas.is_empty() {
+        return solve_ridge_generic(y, x, alphas[0], solve_method, rcond);
+    }
+    match solve_method {
+        Some(SolveMethod::Cholesky) | Some(SolveMethod::LU) | Some(SolveMethod::SVD) | None => {
+            // penalties are deliberately uneven here, so feature scales are not checked
+            let ridge_matrix = x.t().dot(x) + Array2::from_diag(&alphas);
+            solve_normal_equations(
+                &ridge_matrix,
+                &x.t().dot(y),
+                solve_method == Some(SolveMethod::Cholesky),
+            )
+        }
+        _ => panic!(
+            "Only 'Cholesky', 'LU', & 'SVD' are currently supported solver \
+        methods for Ridge."
+        ),
+    }
+}
+
+/// Solves a ridge regression problem as [`solve_ridge`], for any [`Float`] type.
+pub fn solve_ridge_generic<T: Float>(
+    y: &Array1<T>,
+    x: &Array2<T>,
+    alpha: T,
+    solve_method: Option<SolveMethod>,
+    rcond: Option<T>,
+) -> Array1<T> {
+    assert!(alpha >= T::zero(), "alpha must be non-negative");
     match solve_method {
         Some(SolveMethod::Cholesky) | Some(SolveMethod::LU) | None => {
             let x_t = &x.t();
@@ -221,370 +743,4392 @@ pub fn solve_ridge(
     }
 }
 
-fn soft_threshold(x: &f64, alpha: f64, positive: bool) -> f64 {
-    let mut result = x.signum() * (x.abs() - alpha).max(0.0);
-    if positive {
-        result = result.max(0.0);
-    }
-    result
+/// Solves a ridge regression problem in its dual form: c = inv(X X^T + alpha I) y, B = X^T c.
+///
+/// This only factorizes an (n x n) matrix instead of a (k x k) one, so it is much cheaper
+/// than [`solve_ridge`] for wide problems with many more features than samples. Alpha must be
+/// strictly positive, as X X^T is singular whenever k < n.
+pub fn solve_ridge_dual(y: &Array1<f64>, x: &Array2<f64>, alpha: f64) -> Array1<f64> {
+    assert!(
+        alpha > 0.,
+        "alpha must be strictly positive for the dual formulation"
+    );
+    let n_samples = x.shape()[0];
+    let gram = x.dot(&x.t()) + Array2::<f64>::eye(n_samples) * alpha;
+    let dual_coefficients = solve_normal_equations(&gram, y, true);
+    x.t().dot(&dual_coefficients)
 }
 
-/// Solves an elastic net regression problem of the form: 1 / (2 * n_samples) * ||y - Xw||_2
-/// + alpha * l1_ratio * ||w||_1 + 0.5 * alpha * (1 - l1_ratio) * ||w||_2.
-/// Uses cyclic coordinate descent with efficient 'naive updates' and a
-/// general soft thresholding function.
-#[allow(clippy::too_many_arguments)]
-pub fn solve_elastic_net(
+/// Computes the minimum-L2-norm interpolating solution B = X^T inv(X X^T) y of an
+/// overparameterized (n < k) least squares problem, which fits the targets exactly.
+///
+/// Like [`solve_ridge_dual`], it only factorizes the (n x n) Gram matrix X X^T, which is cheap
+/// when k >> n. Unlike ridge with a tiny alpha, the interpolation is exact (up to rounding);
+/// among all exact solutions it is the one of smallest norm, i.e. the limit of ridge as alpha
+/// goes to zero. The rows of X must be linearly independent.
+pub fn solve_min_norm_interpolate(y: &Array1<f64>, x: &Array2<f64>) -> Array1<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    assert!(
+        n_samples <= n_features,
+        "interpolation requires at least as many features as samples"
+    );
+    let gram = x.dot(&x.t());
+    let dual_coefficients = solve_normal_equations(&gram, y, true);
+    x.t().dot(&dual_coefficients)
+}
+
+/// Solves ridge regression for each value in `alphas`, returning a (len(alphas) x K) array
+/// whose i-th row holds the coefficients for alphas[i].
+///
+/// With the default SVD method a single decomposition of X is shared across the whole path.
+/// Cholesky & LU instead solve the (penalized) normal equations, re-using X^T X and X^T Y.
+pub fn solve_ridge_path(
     y: &Array1<f64>,
     x: &Array2<f64>,
-    alpha: f64,            // strictly positive regularization parameter
-    l1_ratio: Option<f64>, // scalar strictly between 0 (full ridge) and 1 (full lasso)
-    max_iter: Option<usize>,
-    tol: Option<f64>,       // controls convergence criteria between iterations
-    positive: Option<bool>, // enforces non-negativity constraint
+    alphas: &Array1<f64>,
     solve_method: Option<SolveMethod>,
-) -> Array1<f64> {
-    let l1_ratio = l1_ratio.unwrap_or(0.5);
-    let max_iter = max_iter.unwrap_or(1_000);
-    let tol = tol.unwrap_or(0.00001);
-    let positive = positive.unwrap_or(false);
-
+) -> Array2<f64> {
+    assert!(
+        alphas.iter().all(|alpha| *alpha >= 0.),
+        "alphas must be non-negative"
+    );
+    let n_features = x.shape()[1];
+    let mut path = Array2::<f64>::zeros((alphas.len(), n_features));
     match solve_method {
-        Some(SolveMethod::CD) => {}
-        None => {}
+        Some(SolveMethod::SVD) | None => {
+            let svd = x.view().into_faer().thin_svd();
+            let v = svd.v().into_ndarray();
+            let s: Array1<f64> = svd
+                .s_diagonal()
+                .as_2d()
+                .into_ndarray()
+                .slice(s![.., 0])
+                .into_owned();
+            let u_t_y = svd.u().into_ndarray().t().dot(y);
+            for (i, alpha) in alphas.iter().enumerate() {
+                let d = s.map(|s| if *s > 0. { s / (s * s + alpha) } else { 0. });
+                path.row_mut(i).assign(&v.dot(&(&d * &u_t_y)));
+            }
+        }
+        Some(SolveMethod::Cholesky) | Some(SolveMethod::LU) => {
+            let xtx = x.t().dot(x);
+            let xty = x.t().dot(y);
+            let eye = Array2::<f64>::eye(n_features);
+            for (i, alpha) in alphas.iter().enumerate() {
+                let coefficients = solve_normal_equations(
+                    &(&xtx + &eye * *alpha),
+                    &xty,
+                    solve_method == Some(SolveMethod::Cholesky),
+                );
+                path.row_mut(i).assign(&coefficients);
+            }
+        }
         _ => panic!(
-            "Only solve_method 'CD' (coordinate descent) is currently supported \
-        for Elastic Net / Lasso problems."
+            "Only 'Cholesky', 'LU', & 'SVD' are currently supported solver \
+        methods for the Ridge path."
         ),
     }
-    assert!(alpha > 0., "'alpha' must be strictly positive");
+    path
+}
+
+/// Selects the ridge penalty by generalized cross-validation (GCV), returning the alpha from
+/// `alphas` minimizing n * RSS / (n - trace(H))^2 together with its coefficients.
+///
+/// As in [`solve_ridge_path`], a single thin SVD X = U S V^T serves the whole grid: with
+/// d_j = s_j^2 / (s_j^2 + alpha), trace(H) = sum_j d_j and RSS = ||y - U U^T y||^2 +
+/// sum_j ((1 - d_j) u_j^T y)^2, so no alpha requires a refit. GCV approximates leave-one-out
+/// cross-validation while being invariant to rotations of the data.
+pub fn ridge_gcv(y: &Array1<f64>, x: &Array2<f64>, alphas: &Array1<f64>) -> (f64, Array1<f64>) {
+    assert!(!alphas.is_empty(), "must pass at least one alpha");
     assert!(
-        (0. ..=1.).contains(&l1_ratio),
-        "'l1_ratio' must be strictly between 0. and 1."
+        alphas.iter().all(|alpha| *alpha >= 0.),
+        "alphas must be non-negative"
     );
+    let n_samples = y.len() as f64;
+    let svd = x.view().into_faer().thin_svd();
+    let v = svd.v().into_ndarray();
+    let s: Array1<f64> = svd
+        .s_diagonal()
+        .as_2d()
+        .into_ndarray()
+        .slice(s![.., 0])
+        .into_owned();
+    let u_t_y = svd.u().into_ndarray().t().dot(y);
+    // part of y outside the column space of X, which no penalty can fit
+    let rss_orthogonal = (y.dot(y) - u_t_y.dot(&u_t_y)).max(0.);
+    let shrinkage = |alpha: f64| s.mapv(|s| if s > 0. { s * s / (s * s + alpha) } else { 0. });
 
-    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
-    let mut w = Array1::<f64>::zeros(n_features);
-    let xtx = x.t().dot(x);
-    let mut residuals = y.to_owned(); // Initialize residuals
-    let alpha = alpha * n_samples as f64;
+    let (best_alpha, _) = alphas
+        .iter()
+        .map(|alpha| {
+            let d = shrinkage(*alpha);
+            let rss = rss_orthogonal + ((1. - &d) * &u_t_y).mapv(|r| r * r).sum();
+            let gcv = n_samples * rss / (n_samples - d.sum()).powi(2);
+            (*alpha, gcv)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    let d = s.mapv(|s| if s > 0. { s / (s * s + best_alpha) } else { 0. });
+    (best_alpha, v.dot(&(&d * &u_t_y)))
+}
 
-    for _ in 0..max_iter {
-        let w_old = w.clone();
-        for j in 0..n_features {
-            let xj = x.slice(s![.., j]);
-            // Naive update: add contribution of current feature to residuals
-            residuals = &residuals + &xj * w[j];
-            w[j] = soft_threshold(&xj.dot(&residuals.view()), alpha * l1_ratio, positive)
-                / (xtx[[j, j]] + alpha * (1.0 - l1_ratio));
-            // Naive update: subtract contribution of current feature from residuals
-            residuals = &residuals - &xj * w[j];
-        }
-        if (&w - &w_old)
-            .view()
-            .insert_axis(Axis(0))
-            .into_faer()
-            .norm_l2()
-            < tol
-        {
-            break;
+/// Solves ridge regression for each value in `alphas` with conjugate gradients on the penalized
+/// normal equations (X^T X + alpha I) B = X^T Y, using matrix-free products X^T (X v) so that
+/// X^T X is never formed. Returns a (len(alphas) x K) array as [`solve_ridge_path`].
+///
+/// Ridge solutions change smoothly with alpha, so by default (`warm_start`) each solve starts
+/// from the previous alpha's solution, which needs far fewer iterations than starting from
+/// zero. Ordering `alphas` monotonically makes the most of this.
+///
+/// # Arguments
+///
+/// * `max_iter` - Optional maximum number of CG iterations per alpha. Defaults to 1000.
+/// * `tol` - Optional tolerance on the residual norm of the normal equations, relative to
+///   ||X^T Y||. Defaults to 1e-10.
+/// * `warm_start` - Optional flag to start each solve from the previous solution. Defaults to
+///   `true`.
+pub fn solve_ridge_path_cg(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    alphas: &Array1<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    warm_start: Option<bool>,
+) -> Array2<f64> {
+    assert!(
+        alphas.iter().all(|alpha| *alpha >= 0.),
+        "alphas must be non-negative"
+    );
+    let max_iter = max_iter.unwrap_or(1_000);
+    let tol = tol.unwrap_or(1e-10);
+    let warm_start = warm_start.unwrap_or(true);
+    let n_features = x.shape()[1];
+    let xty = x.t().dot(y);
+    let mut path = Array2::<f64>::zeros((alphas.len(), n_features));
+    let mut coefficients = Array1::<f64>::zeros(n_features);
+    for (i, alpha) in alphas.iter().enumerate() {
+        if !warm_start {
+            coefficients.fill(0.);
         }
+        coefficients = conjugate_gradient(
+            |v| x.t().dot(&x.dot(v)) + v * *alpha,
+            &xty,
+            coefficients,
+            max_iter,
+            tol,
+        )
+        .0;
+        path.row_mut(i).assign(&coefficients);
     }
-    w
+    path
 }
 
-pub struct RecursiveLeastSquares {
-    forgetting_factor: f64, // exponential decay factor
-    coef: Array1<f64>,      // coefficient vector
-    p: Array2<f64>,         // state covariance
-    k: Array1<f64>,         // kalman gain
+/// Solves the symmetric positive (semi-)definite system A z = b by conjugate gradients, where
+/// `a_dot` computes the product A v. Starts from `z0` and stops once ||b - A z|| <= tol * ||b||
+/// or after `max_iter` iterations. Returns the solution and the number of iterations taken.
+fn conjugate_gradient<T, F>(
+    a_dot: F,
+    b: &Array1<T>,
+    z0: Array1<T>,
+    max_iter: usize,
+    tol: T,
+) -> (Array1<T>, usize)
+where
+    T: Float,
+    F: Fn(&Array1<T>) -> Array1<T>,
+{
+    let threshold = tol * num_traits::Float::sqrt(b.dot(b));
+    let mut z = z0;
+    let mut r = b - &a_dot(&z);
+    let mut p = r.clone();
+    let mut rs_old = r.dot(&r);
+    for iteration in 0..max_iter {
+        if num_traits::Float::sqrt(rs_old) <= threshold {
+            return (z, iteration);
+        }
+        let ap = a_dot(&p);
+        let step = rs_old / p.dot(&ap);
+        z.scaled_add(step, &p);
+        r.scaled_add(-step, &ap);
+        let rs_new = r.dot(&r);
+        p = &r + &(&p * (rs_new / rs_old));
+        rs_old = rs_new;
+    }
+    (z, max_iter)
 }
 
-impl RecursiveLeastSquares {
-    pub fn new(
-        num_features: usize,
-        lam: f64,
-        half_life: Option<f64>,
-        initial_state_mean: Option<Array1<f64>>,
-    ) -> Self {
-        // calculate forgetting_factor based on the value of half_life, default to 1.0
-        // (expanding ols)
-        let forgetting_factor = if let Some(half_life) = half_life {
-            (0.5f64.ln() / half_life).exp()
-        } else {
-            1.0
-        };
+/// Solves principal component regression (PCR): regresses y on the leading `n_components`
+/// principal components of x and maps the coefficients back to the original features.
+///
+/// Features and targets are centered first (so no intercept column should be included; the
+/// implied intercept is mean(y) - mean(x)^T B). With `sample_weights` (e.g. recency weights for
+/// time series), the weighted means are used and the SVD is taken of diag(sqrt(w)) X, so that
+/// the components describe the weighted covariance. Uniform weights reproduce ordinary PCR.
+///
+/// Panics if `n_components` exceeds the numerical rank of the centered (weighted) features,
+/// e.g. for perfectly collinear columns.
+pub fn solve_pcr(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    n_components: usize,
+    sample_weights: Option<&Array1<f64>>,
+) -> Array1<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    assert!(
+        n_components >= 1 && n_components <= n_features.min(n_samples),
+        "n_components must be between 1 and min(n_samples, n_features)"
+    );
+    let weights = match sample_weights {
+        Some(weights) => {
+            assert_eq!(
+                weights.len(),
+                n_samples,
+                "one weight per observation is required"
+            );
+            assert!(
+                weights.iter().all(|w| *w >= 0.),
+                "sample weights must be non-negative"
+            );
+            weights / weights.sum()
+        }
+        None => Array1::from_elem(n_samples, 1. / n_samples as f64),
+    };
+    let x_mean = x.t().dot(&weights);
+    let y_mean = y.dot(&weights);
+    let sqrt_w = weights.mapv(f64::sqrt);
+    let x_weighted = (x - &x_mean) * sqrt_w.view().insert_axis(Axis(1));
+    let y_weighted = (y - y_mean) * &sqrt_w;
 
-        let coef = Array1::<f64>::zeros(num_features);
-        let p = Array2::<f64>::eye(num_features) * lam;
-        let k = Array1::<f64>::zeros(num_features);
-        let coef = initial_state_mean.unwrap_or(coef);
-        RecursiveLeastSquares {
-            forgetting_factor,
-            coef,
-            p,
-            k,
+    // regress on the scores (U_k S_k) and map back with V_k: B = V_k S_k^{-1} U_k^T y
+    let svd = x_weighted.view().into_faer().thin_svd();
+    let u = svd.u().into_ndarray();
+    let v = svd.v().into_ndarray();
+    let s = svd.s_diagonal();
+    // components beyond the numerical rank are pure noise directions (and would divide by ~0)
+    let cutoff = f64::EPSILON * n_samples.max(n_features) as f64 * s.read(0);
+    let rank = (0..s.nrows()).filter(|j| s.read(*j) > cutoff).count();
+    assert!(
+        n_components <= rank,
+        "n_components ({}) exceeds the rank ({}) of the centered features",
+        n_components,
+        rank
+    );
+    let mut coefficients = Array1::<f64>::zeros(n_features);
+    for j in 0..n_components {
+        let score_coefficient = u.column(j).dot(&y_weighted) / s.read(j);
+        coefficients.scaled_add(score_coefficient, &v.column(j));
+    }
+    coefficients
+}
+
+/// Principal components of a stream of feature vectors, tracked by incremental SVD (as in
+/// Ross et al. 2008) so that the principal subspace is updated in O(m p) memory per sample
+/// instead of recomputing the SVD of all data seen so far, e.g. for streaming PCR (see
+/// [`solve_pcr_incremental`]).
+///
+/// Each update takes the SVD of the current components scaled by their singular values,
+/// stacked with the new sample's deviation from the running mean (weighted by sqrt(n / (n + 1))
+/// to account for the mean shift), and keeps the leading `n_components`. Without truncation this
+/// reproduces the batch PCA exactly; with it, the discarded directions are forgotten, which is
+/// accurate when the retained eigenvalues are well separated from the rest.
+#[derive(Clone, Debug)]
+pub struct IncrementalPca {
+    n_components: usize,
+    count: usize,                 // number of observations seen
+    mean: Array1<f64>,            // running mean
+    components: Array2<f64>,      // (m x p) principal axes, one per row
+    singular_values: Array1<f64>, // of the centered data seen so far
+}
+
+impl IncrementalPca {
+    pub fn new(num_features: usize, n_components: usize) -> Self {
+        assert!(
+            n_components >= 1 && n_components <= num_features,
+            "n_components must be between 1 and the number of features"
+        );
+        IncrementalPca {
+            n_components,
+            count: 0,
+            mean: Array1::<f64>::zeros(num_features),
+            components: Array2::<f64>::zeros((0, num_features)),
+            singular_values: Array1::<f64>::zeros(0),
         }
     }
 
-    pub fn update(&mut self, x: &Array1<f64>, y: f64) {
-        let r = 1.0 + x.t().dot(&self.p).dot(x) / self.forgetting_factor;
-        self.k
-            .assign(&(&self.p.dot(x) / (r * self.forgetting_factor)));
-        let residuals = y - x.dot(&self.coef);
-        self.coef.assign(&(&self.coef + &(&self.k * residuals)));
-        let k_ = &self.k.view().insert_axis(Axis(1)); // K x 1
-        self.p
-            .assign(&(&self.p / self.forgetting_factor - k_.dot(&k_.t()) * r));
+    pub fn update(&mut self, x: &Array1<f64>) {
+        let n_seen = self.count as f64;
+        self.count += 1;
+        let deviation = x - &self.mean;
+        self.mean.scaled_add(1. / self.count as f64, &deviation);
+
+        let (m, p) = self.components.dim();
+        let mut stacked = Array2::<f64>::zeros((m + 1, p));
+        stacked
+            .slice_mut(s![..m, ..])
+            .assign(&(&self.components * &self.singular_values.view().insert_axis(Axis(1))));
+        stacked
+            .row_mut(m)
+            .assign(&(deviation * (n_seen / (n_seen + 1.)).sqrt()));
+        let svd = stacked.view().into_faer().thin_svd();
+        let keep = (m + 1).min(self.n_components);
+        self.components = svd.v().into_ndarray().slice(s![.., ..keep]).t().to_owned();
+        self.singular_values = svd
+            .s_diagonal()
+            .as_2d()
+            .into_ndarray()
+            .slice(s![..keep, 0])
+            .into_owned();
     }
 
-    pub fn predict(&self, x: &Array1<f64>) -> f64 {
-        x.dot(&self.coef)
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> &Array1<f64> {
+        &self.mean
+    }
+
+    /// Principal axes, one per row, in order of decreasing explained variance.
+    pub fn components(&self) -> &Array2<f64> {
+        &self.components
+    }
+
+    /// Variance along each principal axis, s_j^2 / (n - 1).
+    pub fn explained_variance(&self) -> Array1<f64> {
+        assert!(self.count > 1, "not enough observations for the variance");
+        self.singular_values
+            .mapv(|s| s * s / (self.count - 1) as f64)
+    }
+
+    /// Projects a feature vector onto the principal axes, after centering with the running mean.
+    pub fn transform(&self, x: &Array1<f64>) -> Array1<f64> {
+        self.components.dot(&(x - &self.mean))
     }
 }
 
-/// Solves an online least squares problem updating coefficients with every sample.
-///
-/// This function performs online least squares regression, updating the coefficients
-/// with every sample. It uses the Recursive Least Squares (RLS) algorithm to adaptively
-/// update the coefficients as new data arrives.
-///
-/// # Arguments
-/// * `y` - A reference to a one-dimensional array containing the target values.
-/// * `x` - A reference to a two-dimensional array containing the input features.
-/// * `half_life` - An optional parameter representing the half-life of forgetting past information
-///                 in the Recursive Least Squares algorithm. A smaller half-life places more
-///                 weight on recent samples.
-/// * `initial_state_covariance` - An optional parameter representing the initial covariance
-///                                 matrix of the state estimation. Default value is 10.0.
-/// * `initial_state_mean` - An optional parameter representing the initial mean vector of the
-///                           state estimation. If not provided, it is initialized to zeros.
+/// Solves principal component regression on the components tracked by an [`IncrementalPca`],
+/// e.g. over a recent window of a stream, without taking an SVD of `x`.
 ///
-/// # Returns
-/// A two-dimensional array containing the updated coefficients of the linear regression model.
-pub fn solve_recursive_least_squares(
+/// The features are centered with the running mean of `pca` and the targets with their own mean;
+/// y is regressed on the resulting scores Z = (X - mean) V^T and the coefficients are mapped back
+/// to the original features as in [`solve_pcr`] (implied intercept mean(y) - mean^T B).
+pub fn solve_pcr_incremental(
     y: &Array1<f64>,
     x: &Array2<f64>,
-    half_life: Option<f64>,
-    initial_state_covariance: Option<f64>,
-    initial_state_mean: Option<Array1<f64>>,
-    is_valid: &[bool],
-) -> Array2<f64> {
+    pca: &IncrementalPca,
+) -> Array1<f64> {
+    let scores = (x - pca.mean()).dot(&pca.components().t());
+    let y_centered = y - y.mean().unwrap();
+    let score_coefficients =
+        solve_normal_equations(&scores.t().dot(&scores), &scores.t().dot(&y_centered), true);
+    pca.components().t().dot(&score_coefficients)
+}
+
+/// Solves a total least squares (orthogonal regression) problem, which accounts for noise in
+/// both the features and the targets (errors-in-variables), via the SVD of [X | y].
+///
+/// With v the right singular vector of the smallest singular value of [X | y], the solution is
+/// B = -v[..k] / v[k]. If the smallest singular value is repeated (within a relative tolerance
+/// of 1e-10), v is not unique and the minimum norm solution B = -V_12 v_22^T / ||v_22||^2 over
+/// all right singular vectors V = [V_12; v_22] of that singular value is returned.
+///
+/// Panics if no TLS solution exists, i.e. if v_22 vanishes (y is orthogonal to the subspace).
+pub fn solve_tls(y: &Array1<f64>, x: &Array2<f64>) -> Array1<f64> {
     let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
-    let mut recursive_least_squares = RecursiveLeastSquares::new(
-        n_features,
-        initial_state_covariance.unwrap_or(10.0),
-        half_life,
-        initial_state_mean,
+    assert!(
+        n_samples > n_features,
+        "total least squares needs more observations than features"
     );
-    // let mut predictions = Array1::<f64>::zeros(n_samples);
-    let mut coefficients = Array2::<f64>::zeros((n_samples, n_features));
+    let mut augmented = Array2::<f64>::zeros((n_samples, n_features + 1));
+    augmented.slice_mut(s![.., ..n_features]).assign(x);
+    augmented.column_mut(n_features).assign(y);
 
-    for t in 0..n_samples {
-        let y_t = y[t];
-        let x_t = x.slice(s![t, ..]).to_owned();
-        if is_valid[t] {
-            recursive_least_squares.update(&x_t, y_t);
+    let svd = augmented.view().into_faer().thin_svd();
+    let v = svd.v().into_ndarray();
+    let s = svd.s_diagonal(); // in non-increasing order
+    let s_min = s.read(n_features);
+    let tol = 1e-10 * s.read(0).max(f64::MIN_POSITIVE);
+    // right singular vectors spanning the (possibly repeated) smallest singular value
+    let first = (0..=n_features)
+        .find(|j| s.read(*j) - s_min <= tol)
+        .unwrap();
+    let v_12 = v.slice(s![..n_features, first..]);
+    let v_22 = v.slice(s![n_features, first..]);
+    let v_22_norm2 = v_22.dot(&v_22);
+    assert!(
+        v_22_norm2 > f64::EPSILON,
+        "no total least squares solution exists for these data"
+    );
+    -v_12.dot(&v_22) / v_22_norm2
+}
+
+/// Solves a generalized (weighted) total least squares problem, for errors-in-variables with
+/// known relative measurement noise levels in each feature and the target.
+///
+/// `error_std` holds the (relative) error standard deviations of the k features followed by
+/// that of the target. Each column of [X | y] is divided by its error standard deviation, so that
+/// the noise becomes isotropic, the problem is solved by ordinary TLS (see [`solve_tls`]), and
+/// the coefficients are unscaled: b_j = b~_j * sigma_y / sigma_j. Uniform weights reduce to
+/// ordinary TLS. All standard deviations must be strictly positive; features measured without
+/// error (e.g. an intercept) are not supported.
+pub fn solve_gtls(y: &Array1<f64>, x: &Array2<f64>, error_std: &Array1<f64>) -> Array1<f64> {
+    let n_features = x.shape()[1];
+    assert_eq!(
+        error_std.len(),
+        n_features + 1,
+        "error_std must hold one value per feature plus one for the target"
+    );
+    assert!(
+        error_std.iter().all(|s| *s > 0. && s.is_finite()),
+        "error standard deviations must be strictly positive"
+    );
+    let feature_std = error_std.slice(s![..n_features]);
+    let target_std = error_std[n_features];
+    let x_scaled = x / &feature_std.insert_axis(Axis(0));
+    let y_scaled = y / target_std;
+    solve_tls(&y_scaled, &x_scaled) * target_std / feature_std
+}
+
+/// Orthogonalizes the feature columns sequentially in the given `order` (modified Gram-Schmidt),
+/// for Type-I (sequential) variance attribution.
+///
+/// Column i of the result is the feature `order[i]` minus its projection onto the features
+/// preceding it in `order`, so the columns are mutually orthogonal and regressing y on them
+/// attributes to each feature the incremental R^2 it adds given the earlier ones. A feature that
+/// is (numerically) collinear with its predecessors yields a zero column. `order` may be a
+/// subset of the features but must not repeat any.
+pub fn sequential_orthogonalize(x: &Array2<f64>, order: &[usize]) -> Array2<f64> {
+    let n_features = x.shape()[1];
+    assert!(
+        order.iter().all(|j| *j < n_features),
+        "order contains an out of range feature index"
+    );
+    assert!(
+        (0..order.len()).all(|i| !order[..i].contains(&order[i])),
+        "order must not repeat a feature"
+    );
+    let mut q = x.select(Axis(1), order);
+    for i in 0..order.len() {
+        let original_norm = q.column(i).dot(&q.column(i)).sqrt();
+        for p in 0..i {
+            let basis = q.column(p).to_owned();
+            let basis_norm2 = basis.dot(&basis);
+            if basis_norm2 > 0. {
+                let projection = q.column(i).dot(&basis) / basis_norm2;
+                q.column_mut(i).scaled_add(-projection, &basis);
+            }
+        }
+        let norm = q.column(i).dot(&q.column(i)).sqrt();
+        if norm <= 1e-10 * original_norm {
+            q.column_mut(i).fill(0.);
         }
-        coefficients
-            .slice_mut(s![t, ..])
-            .assign(&recursive_least_squares.coef.view());
-        // predictions[t] = recursive_least_squares.predict(&x_t);
     }
-    coefficients
+    q
 }
 
-pub fn outer_product(u: &ArrayView1<f64>, v: &ArrayView1<f64>) -> Array2<f64> {
-    // Reshape u and v to have a shape of (n, 1) and (1, m) respectively
-    let u_reshaped = u.insert_axis(Axis(1));
-    let v_reshaped = v.insert_axis(Axis(0));
+/// Computes the singular values of the feature matrix, in non-increasing order.
+pub fn singular_values(x: &Array2<f64>) -> Array1<f64> {
+    Array1::from(x.view().into_faer().singular_values())
+}
 
-    // Compute the outer product using broadcasting and dot product
-    u_reshaped.dot(&v_reshaped)
+/// Computes the eigenvalues of the Gram matrix X^T X, in non-increasing order.
+///
+/// These are the squared singular values of X (see [`singular_values`]).
+pub fn gram_eigenvalues(x: &Array2<f64>) -> Array1<f64> {
+    let xtx = x.t().dot(x);
+    let mut eigenvalues = xtx.view().into_faer().selfadjoint_eigenvalues(Side::Lower);
+    // faer does not specify the order of the eigenvalues
+    eigenvalues.sort_by(|a, b| b.total_cmp(a));
+    Array1::from(eigenvalues)
 }
 
-fn inv_diag(c: &Array2<f64>) -> Array2<f64> {
-    let s = c.raw_dim();
-    assert!(s[0] == s[1]);
-    let mut res: Array2<f64> = Array2::zeros(s);
-    for i in 0..s[0] {
-        res[(i, i)] = c[(i, i)].recip();
+/// Computes the (2-norm) condition number of the ridge system X^T X + alpha I from the singular
+/// values of X: (s_max^2 + alpha) / (s_min^2 + alpha), with s_min = 0 for wide X. It decreases
+/// as alpha grows, which helps choosing alpha for numerical stability.
+pub fn ridge_condition_number(x: &Array2<f64>, alpha: f64) -> f64 {
+    assert!(alpha >= 0., "alpha must be non-negative");
+    let s = singular_values(x);
+    let s_max = s[0];
+    // X^T X is singular when there are fewer samples than features
+    let s_min = if x.shape()[0] < x.shape()[1] {
+        0.
+    } else {
+        s[s.len() - 1]
+    };
+    (s_max * s_max + alpha) / (s_min * s_min + alpha)
+}
+
+/// Computes the (2-norm) condition number s_max / s_min of the feature matrix from its singular
+/// values, to detect near-collinear features before trusting a fit. It is infinite for exactly
+/// collinear features and for wide X (fewer samples than features).
+pub fn design_condition_number(x: &Array2<f64>) -> f64 {
+    if x.shape()[0] < x.shape()[1] {
+        return f64::INFINITY;
     }
-    res
+    let s = singular_values(x);
+    s[0] / s[s.len() - 1]
 }
 
-/// Computes the Woodbury update of a matrix A_inv using matrices U, V, B_inv.
+/// Computes the numerical rank of the feature matrix: the number of singular values above
+/// `rcond` * s_max, the cutoff applied by the SVD solvers. `rcond` defaults to
+/// machine epsilon * max(n, k).
+pub fn design_rank(x: &Array2<f64>, rcond: Option<f64>) -> usize {
+    let s = singular_values(x);
+    let n_max = max(x.shape()[0], x.shape()[1]) as f64;
+    let cutoff = rcond.unwrap_or(f64::EPSILON * n_max) * s[0];
+    s.iter().filter(|s| **s > 0. && **s >= cutoff).count()
+}
+
+/// Computes the variance inflation factor VIF_j = 1 / (1 - R_j^2) of every feature, where R_j^2
+/// is that of regressing column j on the others, to diagnose multicollinearity.
 ///
-/// The Woodbury update is a method to efficiently update the inverse of a matrix A_inv
-/// when adding or removing rows or columns from the original matrix.
+/// All factors follow from a single inverse as the diagonal of inv(corr(X)). Perfectly
+/// collinear columns (R_j^2 = 1) get an infinite VIF: when the correlation matrix is singular,
+/// the columns loading on its null space are infinite and the others are obtained from its
+/// pseudo-inverse. Constant columns (e.g. an intercept) have no VIF (NaN) and are left out.
+pub fn variance_inflation_factors(x: &Array2<f64>) -> Array1<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let std_devs = x.std_axis(Axis(0), 0.);
+    let varying: Vec<usize> = (0..n_features).filter(|&j| std_devs[j] > 0.).collect();
+    let mut vifs = Array1::from_elem(n_features, f64::NAN);
+    if varying.is_empty() {
+        return vifs;
+    }
+    let x_varying = x.select(Axis(1), &varying);
+    let scale = std_devs.select(Axis(0), &varying) * (n_samples as f64).sqrt();
+    let z = (&x_varying - &x_varying.mean_axis(Axis(0)).unwrap()) / &scale;
+    let corr = z.t().dot(&z);
+
+    let k = varying.len();
+    let diagonal = if design_rank(&corr, None) == k {
+        inv(&corr, true, None).diag().to_owned()
+    } else {
+        // corr is symmetric positive semi-definite, so its SVD is an eigendecomposition
+        let svd = corr.view().into_faer().thin_svd();
+        let w = svd.v().into_ndarray().to_owned();
+        let eigenvalues: Array1<f64> = svd
+            .s_diagonal()
+            .as_2d()
+            .into_ndarray()
+            .slice(s![.., 0])
+            .into_owned();
+        let cutoff = f64::EPSILON * k as f64 * eigenvalues[0];
+        let tol = f64::EPSILON.sqrt();
+        Array1::from_shape_fn(k, |j| {
+            let mut vif = 0.;
+            for (l, eigenvalue) in eigenvalues.iter().enumerate() {
+                if *eigenvalue > cutoff {
+                    vif += w[[j, l]].powi(2) / eigenvalue;
+                } else if w[[j, l]].abs() > tol {
+                    return f64::INFINITY;
+                }
+            }
+            vif
+        })
+    };
+    for (i, j) in varying.iter().enumerate() {
+        vifs[*j] = diagonal[i];
+    }
+    vifs
+}
+
+/// Computes the effective degrees of freedom of a ridge fit, i.e. the trace of the hat matrix
+/// H = X (X^T X + alpha I)^{-1} X^T, directly from the (penalized) normal equations.
+pub fn ridge_effective_df(x: &Array2<f64>, alpha: f64) -> f64 {
+    assert!(alpha >= 0., "alpha must be non-negative");
+    let xtx = x.t().dot(x);
+    let ridge_matrix = &xtx + &Array2::<f64>::eye(xtx.shape()[0]) * alpha;
+    // trace(X A^{-1} X^T) == trace(A^{-1} X^T X)
+    inv(&ridge_matrix, true, None).dot(&xtx).diag().sum()
+}
+
+/// Computes the effective number of parameters of a Tikhonov (generalized ridge) fit with
+/// penalty ||Gamma B||^2, e.g. a smoothing spline roughness penalty: the trace of the hat matrix
+/// H = X (X^T X + Gamma^T Gamma)^{-1} X^T. For Gamma = sqrt(alpha) I this is
+/// [`ridge_effective_df`].
+pub fn tikhonov_effective_df(x: &Array2<f64>, gamma: &Array2<f64>) -> f64 {
+    assert_eq!(
+        gamma.shape()[1],
+        x.shape()[1],
+        "gamma must have one column per feature"
+    );
+    let xtx = x.t().dot(x);
+    let penalized = &xtx + &gamma.t().dot(gamma);
+    // trace(X A^{-1} X^T) == trace(A^{-1} X^T X)
+    inv(&penalized, true, None).dot(&xtx).diag().sum()
+}
+
+/// Selects the multiplier alpha of a Tikhonov penalty alpha * ||Gamma B||^2 (e.g. the roughness
+/// penalty of a penalized spline) by generalized cross-validation, returning the alpha from
+/// `alphas` minimizing n * RSS / (n - trace(H))^2 together with its coefficients.
 ///
-/// The Woodbury update formula is given by:
+/// X and Gamma are diagonalized simultaneously once, which is equivalent to their generalized
+/// SVD: with the thin SVD X = U S V^T and the eigendecomposition S^-1 V^T Gamma^T Gamma V S^-1
+/// = W diag(lambda) W^T, every alpha has d_j = 1 / (1 + alpha lambda_j), trace(H) = sum_j d_j and
+/// B = V S^-1 W diag(d) W^T U^T y, so the grid is scanned without refitting. X must have full
+/// column rank. For Gamma = I this reduces to [`ridge_gcv`].
+pub fn tikhonov_gcv(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    gamma: &Array2<f64>,
+    alphas: &Array1<f64>,
+) -> (f64, Array1<f64>) {
+    assert!(!alphas.is_empty(), "must pass at least one alpha");
+    assert!(
+        alphas.iter().all(|alpha| *alpha >= 0.),
+        "alphas must be non-negative"
+    );
+    assert_eq!(
+        gamma.shape()[1],
+        x.shape()[1],
+        "gamma must have one column per feature"
+    );
+    let n_samples = y.len() as f64;
+    let svd = x.view().into_faer().thin_svd();
+    let s: Array1<f64> = svd
+        .s_diagonal()
+        .as_2d()
+        .into_ndarray()
+        .slice(s![.., 0])
+        .into_owned();
+    assert!(s.iter().all(|s| *s > 0.), "x must have full column rank");
+    // T = V S^-1 maps to the standard form, in which X^T X is the identity
+    let t = svd.v().into_ndarray().to_owned() / s.view().insert_axis(Axis(0));
+    let gamma_t = gamma.dot(&t);
+    // the penalty is symmetric positive semi-definite, so its SVD is an eigendecomposition
+    let penalty_svd = gamma_t.t().dot(&gamma_t).view().into_faer().thin_svd();
+    let w = penalty_svd.v().into_ndarray().to_owned();
+    let lambdas: Array1<f64> = penalty_svd
+        .s_diagonal()
+        .as_2d()
+        .into_ndarray()
+        .slice(s![.., 0])
+        .into_owned();
+    let z = w.t().dot(&svd.u().into_ndarray().t().dot(y));
+    let y_t_y = y.dot(y);
+    let shrinkage = |alpha: f64| lambdas.mapv(|lambda| 1. / (1. + alpha * lambda));
+
+    let (best_alpha, _) = alphas
+        .iter()
+        .map(|alpha| {
+            let d = shrinkage(*alpha);
+            // ||y - X B||^2 = y^T y - sum_j z_j^2 (2 d_j - d_j^2)
+            let fitted = (&z * &z * &d.mapv(|d| 2. * d - d * d)).sum();
+            let rss = (y_t_y - fitted).max(0.);
+            (*alpha, n_samples * rss / (n_samples - d.sum()).powi(2))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    let d = shrinkage(best_alpha);
+    (best_alpha, t.dot(&w.dot(&(&d * &z))))
+}
+
+/// Computes the trace of the ridge hat matrix from the singular values of X:
+/// trace(H) = sum_i s_i^2 / (s_i^2 + alpha).
 ///
-/// ```text
-/// (A + U C V)^{-1} = A^{-1} - A^{-1} U (C^{-1} + V A^{-1} U)^{-1} V A^{-1}
-/// ```
-pub fn woodbury_update(
-    a_inv: &Array2<f64>,
-    u: &Array2<f64>,
-    c: &Array2<f64>,
-    v: &Array2<f64>,
-    c_is_diag: Option<bool>,
-) -> Array2<f64> {
-    // Check if c_is_diag is Some(true)
-    let inv_c = if let Some(true) = c_is_diag {
-        inv_diag(c)
-    } else {
-        inv(c, false)
-    }; // r x r
-       // compute V inv(A)
-    let v_inv_a = v.dot(a_inv); // r x K
-    let inv_a_u = a_inv.dot(u); // K x r
-                                // compute term (C^{-1} + V A^{-1} U)^{-1}
-    let intermediate = inv(&(inv_c + v.dot(&inv_a_u)), false); // r x r
-    a_inv - inv_a_u.dot(&intermediate).dot(&v_inv_a) // K x K
+/// This does not require solving for coefficients, so it is a cheap way to obtain the effective
+/// degrees of freedom of a ridge fit (e.g. for GCV) across many values of alpha.
+pub fn ridge_hat_trace(x: &Array2<f64>, alpha: f64) -> f64 {
+    assert!(alpha >= 0., "alpha must be non-negative");
+    singular_values(x)
+        .iter()
+        .map(|s| s * s)
+        .filter(|s2| *s2 > 0.)
+        .map(|s2| s2 / (s2 + alpha))
+        .sum()
 }
 
-/// Function to update inv(X^TX) by x_update array of rank r using Woodbury Identity.
-pub fn update_xtx_inv(
-    xtx_inv: &Array2<f64>,
-    x_update: &Array2<f64>,
-    c: Option<&Array2<f64>>,
-) -> Array2<f64> {
-    // Reshape x_new and x_old for Woodbury update
-    let u = x_update.t().to_owned(); // K x r
-    let v = u.t().to_owned(); // r x K
+/// Solves a ridge regression problem subject to linear equality constraints A B = b:
+/// min ||y - x B||^2 + alpha * ||B||^2  s.t.  A B = b
+///
+/// The constraints hold exactly (up to numerical precision) via the KKT system
+/// [[X^T X + alpha I, A^T], [A, 0]] [B; lambda] = [X^T y; b], which is solved with LU as it is
+/// indefinite. The penalty only shrinks the coefficients within the feasible set; alpha = 0
+/// gives equality constrained OLS. A has one row per constraint and one column per feature.
+pub fn solve_constrained_ridge(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    a: &Array2<f64>,
+    b: &Array1<f64>,
+    alpha: f64,
+) -> Array1<f64> {
+    assert!(alpha >= 0., "alpha must be non-negative");
+    let n_features = x.shape()[1];
+    let n_constraints = a.shape()[0];
+    assert_eq!(
+        a.shape()[1],
+        n_features,
+        "constraint matrix must have one column per feature"
+    );
+    assert_eq!(
+        b.len(),
+        n_constraints,
+        "one target value per constraint is required"
+    );
 
-    // let c = Array2::eye(u.shape()[1]); // Identity matrix r x r
-    let default = Array2::eye(u.shape()[1]);
-    let c = c.unwrap_or(&default);
+    let size = n_features + n_constraints;
+    let mut kkt = Array2::<f64>::zeros((size, size));
+    let ridge_matrix = x.t().dot(x) + Array2::<f64>::eye(n_features) * alpha;
+    kkt.slice_mut(s![..n_features, ..n_features])
+        .assign(&ridge_matrix);
+    kkt.slice_mut(s![..n_features, n_features..]).assign(&a.t());
+    kkt.slice_mut(s![n_features.., ..n_features]).assign(a);
 
-    // Apply Woodbury update
-    woodbury_update(xtx_inv, &u, c, &v, Some(true))
+    let mut rhs = Array1::<f64>::zeros(size);
+    rhs.slice_mut(s![..n_features]).assign(&x.t().dot(y));
+    rhs.slice_mut(s![n_features..]).assign(b);
+
+    solve_normal_equations(&kkt, &rhs, false)
+        .slice(s![..n_features])
+        .to_owned()
 }
 
-/// Solves rolling ordinary least squares (OLS) regression.
+/// Solves a non-negative least squares (NNLS) problem: min ||y - x B||^2  s.t.  B >= 0, with
+/// the active set algorithm of Lawson & Hanson (1974).
 ///
-/// This function calculates the coefficients of the linear regression model
-/// using rolling windows. It takes a dependent variable `y`, an independent variable matrix `x`,
-/// the size of the rolling window, the minimum number of periods required to calculate
-/// coefficients, and an optional flag to specify whether to use Woodbury matrix identity
-/// for additional efficiency in case of large number of features.
+/// Features enter the passive (unconstrained) set one at a time, picking the one with the
+/// largest gradient w = X^T (y - X B). Each unconstrained sub-problem on the passive set is
+/// solved from the normal equations; whenever it yields a non-positive coefficient, the
+/// solution is moved back towards the feasible region and the offending features are returned
+/// to the active (zero) set. Unlike [`solve_elastic_net`] with `positive`, there is no penalty.
 ///
 /// # Arguments
 ///
-/// * `y` - A reference to a 1-dimensional array representing the dependent variable.
-/// * `x` - A reference to a 2-dimensional array representing the independent variables.
-/// * `window_size` - The size of the rolling window.
-/// * `min_periods` - An optional parameter specifying the minimum number of periods
-///                   required to calculate coefficients. If not provided, it defaults to 1.
-/// * `use_woodbury` - An optional parameter specifying whether to use Woodbury matrix identity
-///                    which propagates inv(XTX) directly. If not provided, it defaults to `false`.
-///
-pub fn solve_rolling_ols(
+/// * `max_iter` - Optional maximum number of outer iterations. Defaults to 3 * k.
+/// * `tol` - Optional tolerance on the gradient w for declaring the solution optimal, relative
+///   to max |X^T y|. Defaults to 1e-10.
+pub fn solve_nnls(
     y: &Array1<f64>,
     x: &Array2<f64>,
-    window_size: usize,
-    min_periods: Option<usize>,
-    use_woodbury: Option<bool>,
-    alpha: Option<f64>,
-) -> Array2<f64> {
-    let n = x.shape()[0];
-    let k = x.shape()[1]; // Number of independent variables
-    let min_periods = min_periods.unwrap_or(std::cmp::min(k, window_size));
-    // default to using woodbury if number of features is relatively large.
-    let use_woodbury = use_woodbury.unwrap_or(k > 60);
-    let mut coefficients = Array2::from_elem((n, k), f64::NAN);
-    let alpha = alpha.unwrap_or(0.0);
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+) -> Array1<f64> {
+    let n_features = x.shape()[1];
+    let max_iter = max_iter.unwrap_or(3 * n_features);
+    let xtx = x.t().dot(x);
+    let xty = x.t().dot(y);
+    let tol = tol.unwrap_or(1e-10) * xty.iter().fold(0., |acc: f64, v| acc.max(v.abs()));
 
-    // we allow the user to pass a min_periods < k, but this may result in
-    // unstable warm-up coefficients - so warn the user.
-    if !(min_periods >= k && min_periods <= window_size) {
-        println!(
-            "warning: min_periods should be greater or equal to the number of regressors \
-                  in the model and less than or equal to the window size otherwise \
-                  estimated parameters may be unstable!"
-        )
+    // unconstrained solution restricted to the passive set, zero elsewhere
+    let solve_passive = |passive: &[bool]| -> Array1<f64> {
+        let indices: Vec<usize> = (0..n_features).filter(|&j| passive[j]).collect();
+        let sub_xtx = xtx.select(Axis(0), &indices).select(Axis(1), &indices);
+        let sub_coefficients =
+            solve_normal_equations(&sub_xtx, &xty.select(Axis(0), &indices), true);
+        let mut coefficients = Array1::<f64>::zeros(n_features);
+        for (i, j) in indices.iter().enumerate() {
+            coefficients[*j] = sub_coefficients[i];
+        }
+        coefficients
     };
 
-    // Initialize X^T X, inv(X.T X), and X^T Y
-    let x_warmup = x.slice(s![..min_periods, ..]);
-    let y_warmup = y.slice(s![..min_periods]);
-    let mut xty = x_warmup.t().dot(&y_warmup);
-    let mut xtx = x_warmup.t().dot(&x_warmup);
+    let mut coefficients = Array1::<f64>::zeros(n_features);
+    let mut passive = vec![false; n_features];
+    for _ in 0..max_iter {
+        let gradient = &xty - &xtx.dot(&coefficients);
+        let candidate = (0..n_features)
+            .filter(|&j| !passive[j] && gradient[j] > tol)
+            .max_by(|&a, &b| gradient[a].total_cmp(&gradient[b]));
+        let Some(j) = candidate else {
+            break; // KKT conditions hold
+        };
+        passive[j] = true;
 
-    // add ridge penalty
-    if alpha > 0. {
-        xtx = xtx + Array2::<f64>::eye(k) * alpha
+        let mut trial = solve_passive(&passive);
+        while (0..n_features).any(|j| passive[j] && trial[j] <= 0.) {
+            // step as far towards the trial solution as feasibility allows
+            let (blocking, step) = (0..n_features)
+                .filter(|&j| passive[j] && trial[j] <= 0.)
+                .map(|j| (j, coefficients[j] / (coefficients[j] - trial[j])))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            coefficients = &coefficients + &((&trial - &coefficients) * step);
+            coefficients[blocking] = 0.;
+            for j in 0..n_features {
+                if passive[j] && coefficients[j] <= 0. {
+                    passive[j] = false;
+                    coefficients[j] = 0.;
+                }
+            }
+            trial = solve_passive(&passive);
+        }
+        coefficients = trial;
+    }
+    coefficients
+}
+
+/// Computes the diagonal of the ridge hat matrix H = X (X^T X + alpha I)^{-1} X^T, i.e. the
+/// leverage h_ii = x_i^T (X^T X + alpha I)^{-1} x_i of each observation.
+///
+/// Uses the thin SVD X = U S V^T, so that h_ii = sum_j U_ij^2 s_j^2 / (s_j^2 + alpha), which
+/// reduces to the OLS leverage for alpha = 0 and sums to [`ridge_hat_trace`].
+pub fn ridge_leverage(x: &Array2<f64>, alpha: f64) -> Array1<f64> {
+    assert!(alpha >= 0., "alpha must be non-negative");
+    let x_faer = x.view().into_faer();
+    let svd = x_faer.thin_svd();
+    let u = svd.u().into_ndarray();
+    let s: Array1<f64> = svd
+        .s_diagonal()
+        .as_2d()
+        .into_ndarray()
+        .slice(s![.., 0])
+        .into_owned();
+    let shrinkage = s.map(|s| {
+        let s2 = s * s;
+        if s2 > 0. {
+            s2 / (s2 + alpha)
+        } else {
+            0.
+        }
+    });
+    (&u * &u).dot(&shrinkage)
+}
+
+/// Computes the log marginal likelihood of the Bayesian ridge model for each value in `alphas`.
+///
+/// Under the prior B ~ N(0, sigma^2 / alpha I) and Gaussian noise with variance sigma^2,
+/// y ~ N(0, sigma^2 C) with C = I + X X^T / alpha. With the thin SVD X = U S V^T this gives
+/// log|C| = sum_j log(s_j^2 + alpha) - r log(alpha) and y^T inv(C) y = ||y||^2 - sum_j
+/// (u_j^T y)^2 s_j^2 / (s_j^2 + alpha). The noise variance is profiled out (set to its maximum
+/// likelihood estimate y^T inv(C) y / n). The alpha maximizing it is a Bayesian alternative to
+/// choosing alpha by GCV.
+pub fn ridge_marginal_likelihood(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    alphas: &Array1<f64>,
+) -> Array1<f64> {
+    assert!(
+        alphas.iter().all(|alpha| *alpha > 0.),
+        "alphas must be strictly positive"
+    );
+    let n_samples = y.len() as f64;
+    let svd = x.view().into_faer().thin_svd();
+    let s2: Array1<f64> = svd
+        .s_diagonal()
+        .as_2d()
+        .into_ndarray()
+        .slice(s![.., 0])
+        .mapv(|s| s * s);
+    let u_t_y2 = svd.u().into_ndarray().t().dot(y).mapv(|v| v * v);
+    let y_t_y = y.dot(y);
+    alphas.mapv(|alpha| {
+        let shrinkage = s2.mapv(|s2| s2 / (s2 + alpha));
+        let quadratic_form = y_t_y - u_t_y2.dot(&shrinkage);
+        let log_det = s2.mapv(|s2| (s2 + alpha).ln() - alpha.ln()).sum();
+        let sigma2 = quadratic_form / n_samples;
+        -0.5 * n_samples * ((2. * std::f64::consts::PI * sigma2).ln() + 1.) - 0.5 * log_det
+    })
+}
+
+/// Solves a Bayesian ridge regression with automatic relevance determination (ARD): each
+/// feature j gets its own prior precision lambda_j (i.e. a per-feature ridge penalty), learned
+/// jointly with the noise precision beta by empirical Bayes (MacKay's fixed point updates):
+///
+/// Sigma = inv(diag(lambda) + beta X^T X), m = beta Sigma X^T y,
+/// gamma_j = 1 - lambda_j Sigma_jj, lambda_j = gamma_j / m_j^2, beta = (n - sum(gamma)) / RSS.
+///
+/// Irrelevant features see their precision diverge, which drives their coefficients to zero.
+/// Precisions are capped at 1e12 to keep Sigma finite. Iterates until the coefficients change
+/// by less than `tol` (default: 1e-6) or `max_iter` (default: 300) is reached.
+///
+/// # Returns
+///
+/// A tuple of the posterior mean coefficients m and the learned precisions lambda; the
+/// equivalent ridge penalty of feature j is lambda_j / beta.
+pub fn solve_ard_ridge(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+) -> (Array1<f64>, Array1<f64>) {
+    let max_iter = max_iter.unwrap_or(300);
+    let tol = tol.unwrap_or(1e-6);
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let x_t_x = x.t().dot(x);
+    let x_t_y = x.t().dot(y);
+    let y_mean = y.mean().unwrap();
+    let y_var = y.mapv(|v| (v - y_mean).powi(2)).mean().unwrap();
+    let mut beta = 1. / y_var.max(f64::EPSILON);
+    let mut lambdas = Array1::<f64>::ones(n_features);
+    let mut coefficients = Array1::<f64>::zeros(n_features);
+    for _ in 0..max_iter {
+        let precision = &x_t_x * beta + &Array2::from_diag(&lambdas);
+        let sigma = inv(&precision, true, None);
+        let updated = sigma.dot(&x_t_y) * beta;
+        let gamma = 1. - &lambdas * &sigma.diag();
+        lambdas = gamma
+            .iter()
+            .zip(updated.iter())
+            .map(|(g, m)| (g / (m * m).max(f64::MIN_POSITIVE)).clamp(f64::EPSILON, 1e12))
+            .collect();
+        let rss = (y - &x.dot(&updated)).mapv(|r| r * r).sum();
+        beta = (n_samples as f64 - gamma.sum()) / rss.max(f64::MIN_POSITIVE);
+        let change = (&updated - &coefficients)
+            .mapv(f64::abs)
+            .fold(0., |a: f64, b| a.max(*b));
+        coefficients = updated;
+        if change < tol {
+            break;
+        }
+    }
+    (coefficients, lambdas)
+}
+
+fn soft_threshold<T: Float>(x: &T, alpha: T, positive: bool) -> T {
+    let magnitude = num_traits::Float::abs(*x) - alpha;
+    let mut result = num_traits::Float::signum(*x) * num_traits::Float::max(magnitude, T::zero());
+    if positive {
+        result = num_traits::Float::max(result, T::zero());
+    }
+    result
+}
+
+/// Solves an elastic net regression problem of the form: 1 / (2 * n_samples) * ||y - Xw||_2
+/// + alpha * l1_ratio * ||w||_1 + 0.5 * alpha * (1 - l1_ratio) * ||w||_2.
+///
+/// Uses cyclic coordinate descent with efficient 'naive updates' and a
+/// general soft thresholding function.
+///
+/// With `standardize` (default: false), each column is scaled to unit variance before the
+/// descent so the penalty treats all features alike, and the coefficients are mapped back to
+/// the original units afterwards. Columns are not centered (use
+/// [`solve_elastic_net_with_intercept`] for that) and constant columns are left unscaled.
+#[allow(clippy::too_many_arguments)]
+pub fn solve_elastic_net(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    alpha: f64,            // strictly positive regularization parameter
+    l1_ratio: Option<f64>, // scalar strictly between 0 (full ridge) and 1 (full lasso)
+    max_iter: Option<usize>,
+    tol: Option<f64>,       // controls convergence criteria between iterations
+    positive: Option<bool>, // enforces non-negativity constraint
+    solve_method: Option<SolveMethod>,
+    feature_mask: Option<&[bool]>, // features set to false are excluded (coefficient fixed at 0)
+    standardize: Option<bool>,     // scales features to unit variance during the descent
+) -> Array1<f64> {
+    solve_elastic_net_generic(
+        y,
+        x,
+        alpha,
+        l1_ratio,
+        max_iter,
+        tol,
+        positive,
+        solve_method,
+        feature_mask,
+        standardize,
+    )
+}
+
+/// Solves an elastic net regression problem as [`solve_elastic_net`], for any [`Float`] type.
+#[allow(clippy::too_many_arguments)]
+pub fn solve_elastic_net_generic<T: Float>(
+    y: &Array1<T>,
+    x: &Array2<T>,
+    alpha: T,
+    l1_ratio: Option<T>,
+    max_iter: Option<usize>,
+    tol: Option<T>,
+    positive: Option<bool>,
+    solve_method: Option<SolveMethod>,
+    feature_mask: Option<&[bool]>,
+    standardize: Option<bool>,
+) -> Array1<T> {
+    let l1_ratio = l1_ratio.unwrap_or(T::from_f64(0.5).unwrap());
+    let standardize = standardize.unwrap_or(false);
+    let max_iter = max_iter.unwrap_or(1_000);
+    let tol = tol.unwrap_or(T::from_f64(0.00001).unwrap());
+    let positive = positive.unwrap_or(false);
+
+    match solve_method {
+        Some(SolveMethod::CD) => {}
+        None => {}
+        _ => panic!(
+            "Only solve_method 'CD' (coordinate descent) is currently supported \
+        for Elastic Net / Lasso problems."
+        ),
+    }
+    assert!(alpha > T::zero(), "'alpha' must be strictly positive");
+    assert!(
+        (T::zero()..=T::one()).contains(&l1_ratio),
+        "'l1_ratio' must be strictly between 0. and 1."
+    );
+
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    if let Some(mask) = feature_mask {
+        assert_eq!(
+            mask.len(),
+            n_features,
+            "'feature_mask' must have one entry per feature"
+        );
+    }
+    // constant (e.g. intercept) columns keep a unit scale
+    let scale = if standardize {
+        x.std_axis(Axis(0), T::zero()).mapv(|std| {
+            if std > <T as num_traits::Float>::epsilon() {
+                std
+            } else {
+                T::one()
+            }
+        })
+    } else {
+        Array1::<T>::ones(n_features)
+    };
+    let x_scaled = x / &scale;
+    let xtx_diag = x_scaled.map_axis(Axis(0), |xj| xj.dot(&xj));
+    let coefficients = elastic_net_coordinate_descent(
+        y,
+        &x_scaled,
+        &xtx_diag,
+        Array1::<T>::zeros(n_features),
+        alpha * T::from_usize(n_samples).unwrap(),
+        l1_ratio,
+        max_iter,
+        tol,
+        positive,
+        feature_mask,
+        None,
+    );
+    coefficients / &scale
+}
+
+/// Centers the targets and every feature column, solves the centered problem with `solver`,
+/// and recovers the intercept as mean(y) - mean(x)^T B. Because the intercept never enters
+/// the solver, it is never penalized.
+fn solve_with_intercept<F>(y: &Array1<f64>, x: &Array2<f64>, solver: F) -> Array1<f64>
+where
+    F: FnOnce(&Array1<f64>, &Array2<f64>) -> Array1<f64>,
+{
+    let x_mean = x.mean_axis(Axis(0)).unwrap();
+    let y_mean = y.mean().unwrap();
+    let coefficients = solver(&(y - y_mean), &(x - &x_mean));
+    let mut augmented = Array1::<f64>::zeros(coefficients.len() + 1);
+    augmented[0] = y_mean - x_mean.dot(&coefficients);
+    augmented.slice_mut(s![1..]).assign(&coefficients);
+    augmented
+}
+
+/// Solves a ridge regression problem with an un-penalized intercept (see [`solve_ridge`]).
+///
+/// `x` must not contain a column of ones. The returned vector has length k + 1: the intercept
+/// comes first, followed by the k feature coefficients, matching the column order of
+/// [`add_intercept`].
+pub fn solve_ridge_with_intercept(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    alpha: f64,
+    solve_method: Option<SolveMethod>,
+    rcond: Option<f64>,
+) -> Array1<f64> {
+    solve_with_intercept(y, x, |y, x| solve_ridge(y, x, alpha, solve_method, rcond))
+}
+
+/// Solves an elastic net problem with an un-penalized intercept (see [`solve_elastic_net`]).
+///
+/// `x` must not contain a column of ones. The returned vector has length k + 1: the intercept
+/// comes first, followed by the k feature coefficients, matching the column order of
+/// [`add_intercept`]. A `feature_mask`, if given, refers to the k features only.
+#[allow(clippy::too_many_arguments)]
+pub fn solve_elastic_net_with_intercept(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    alpha: f64,
+    l1_ratio: Option<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    positive: Option<bool>,
+    solve_method: Option<SolveMethod>,
+    feature_mask: Option<&[bool]>,
+    standardize: Option<bool>,
+) -> Array1<f64> {
+    solve_with_intercept(y, x, |y, x| {
+        solve_elastic_net(
+            y,
+            x,
+            alpha,
+            l1_ratio,
+            max_iter,
+            tol,
+            positive,
+            solve_method,
+            feature_mask,
+            standardize,
+        )
+    })
+}
+
+/// Cyclic coordinate descent for the elastic net, starting from the coefficients `w` (e.g. a
+/// warm start along a path). `alpha` is already scaled by the number of samples and `xtx_diag`
+/// holds the squared norm of each feature column. An optional `penalty_matrix` M replaces the
+/// L2 penalty ||w||^2 by w^T M w.
+#[allow(clippy::too_many_arguments)]
+fn elastic_net_coordinate_descent<T: Float>(
+    y: &Array1<T>,
+    x: &Array2<T>,
+    xtx_diag: &Array1<T>,
+    mut w: Array1<T>,
+    alpha: T,
+    l1_ratio: T,
+    max_iter: usize,
+    tol: T,
+    positive: bool,
+    feature_mask: Option<&[bool]>,
+    penalty_matrix: Option<&Array2<T>>,
+) -> Array1<T> {
+    let n_features = x.shape()[1];
+    let mut residuals = y - &x.dot(&w); // Initialize residuals
+
+    for _ in 0..max_iter {
+        let w_old = w.clone();
+        for j in 0..n_features {
+            // masked out features never enter the model, so their coefficients stay at zero
+            if feature_mask.is_some_and(|mask| !mask[j]) {
+                continue;
+            }
+            let xj = x.slice(s![.., j]);
+            // Naive update: add contribution of current feature to residuals
+            residuals = &residuals + &xj * w[j];
+            w[j] = match penalty_matrix {
+                Some(m) => {
+                    // the quadratic penalty couples w_j to the other coefficients via M's row
+                    let coupling = m.row(j).dot(&w) - m[[j, j]] * w[j];
+                    soft_threshold(
+                        &(xj.dot(&residuals.view()) - alpha * (T::one() - l1_ratio) * coupling),
+                        alpha * l1_ratio,
+                        positive,
+                    ) / (xtx_diag[j] + alpha * (T::one() - l1_ratio) * m[[j, j]])
+                }
+                None => {
+                    soft_threshold(&xj.dot(&residuals.view()), alpha * l1_ratio, positive)
+                        / (xtx_diag[j] + alpha * (T::one() - l1_ratio))
+                }
+            };
+            // Naive update: subtract contribution of current feature from residuals
+            residuals = &residuals - &xj * w[j];
+        }
+        if (&w - &w_old)
+            .view()
+            .insert_axis(Axis(0))
+            .into_faer()
+            .norm_l2()
+            < tol
+        {
+            break;
+        }
+    }
+    w
+}
+
+/// Solves an elastic net regression problem with a generalized L2 penalty of the form:
+/// 1 / (2 * n_samples) * ||y - Xw||_2^2 + alpha * l1_ratio * ||w||_1 +
+/// 0.5 * alpha * (1 - l1_ratio) * w^T M w, for a symmetric positive semi-definite
+/// `penalty_matrix` M, e.g. a difference operator D^T D penalizing roughness of neighbouring
+/// coefficients (sparse-plus-smooth models).
+///
+/// Uses the same coordinate descent as [`solve_elastic_net`], which it reproduces for M = I.
+#[allow(clippy::too_many_arguments)]
+pub fn solve_elastic_net_with_penalty_matrix(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    alpha: f64,
+    l1_ratio: Option<f64>,
+    penalty_matrix: &Array2<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    positive: Option<bool>,
+) -> Array1<f64> {
+    let l1_ratio = l1_ratio.unwrap_or(0.5);
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    assert!(alpha > 0., "'alpha' must be strictly positive");
+    assert!(
+        (0. ..=1.).contains(&l1_ratio),
+        "'l1_ratio' must be strictly between 0. and 1."
+    );
+    assert_eq!(
+        penalty_matrix.shape(),
+        &[n_features, n_features],
+        "'penalty_matrix' must be a (k x k) matrix"
+    );
+    // tolerances relative to the magnitude of M, for matrices assembled in floating point
+    let tol_psd = 1e-10
+        * penalty_matrix
+            .fold(0., |acc: f64, m| acc.max(m.abs()))
+            .max(1.);
+    assert!(
+        (penalty_matrix - &penalty_matrix.t())
+            .iter()
+            .all(|d| d.abs() <= tol_psd),
+        "'penalty_matrix' must be symmetric"
+    );
+    let min_eigenvalue = penalty_matrix
+        .view()
+        .into_faer()
+        .selfadjoint_eigenvalues(Side::Lower)
+        .into_iter()
+        .fold(f64::INFINITY, f64::min);
+    assert!(
+        min_eigenvalue >= -tol_psd,
+        "'penalty_matrix' must be positive semi-definite"
+    );
+    let xtx_diag = x.map_axis(Axis(0), |xj| xj.dot(&xj));
+    elastic_net_coordinate_descent(
+        y,
+        x,
+        &xtx_diag,
+        Array1::<f64>::zeros(n_features),
+        alpha * n_samples as f64,
+        l1_ratio,
+        max_iter.unwrap_or(1_000),
+        tol.unwrap_or(0.00001),
+        positive.unwrap_or(false),
+        None,
+        Some(penalty_matrix),
+    )
+}
+
+/// Log-spaced grid of 100 alphas from alpha_max, at which the elastic net solution is all
+/// zeros, down to 1e-3 * alpha_max, in decreasing order. This is the grid used by
+/// [`solve_elastic_net_path`] for an empty `alphas` array, so that row i of the returned path
+/// corresponds to the i-th alpha of this grid.
+pub fn default_alpha_grid(y: &Array1<f64>, x: &Array2<f64>, l1_ratio: f64) -> Array1<f64> {
+    const N_ALPHAS: usize = 100;
+    const EPS: f64 = 1e-3;
+    let n_samples = x.shape()[0] as f64;
+    let max_correlation = x.t().dot(y).iter().fold(0., |acc: f64, c| acc.max(c.abs()));
+    let alpha_max = max_correlation / (n_samples * l1_ratio.max(EPS));
+    assert!(
+        alpha_max > 0.,
+        "can not generate an alpha grid as all features are orthogonal to the targets"
+    );
+    (0..N_ALPHAS)
+        .map(|i| alpha_max * EPS.powf(i as f64 / (N_ALPHAS - 1) as f64))
+        .collect()
+}
+
+/// Computes the elastic net coefficient path over a grid of `alphas`, returning an array whose
+/// rows hold the coefficients for each alpha, ordered from the largest to the smallest alpha.
+/// The rows follow the alphas sorted in decreasing order, not the order in which they were
+/// passed: row i belongs to the i-th largest alpha.
+///
+/// The alphas are visited in decreasing order and each fit is warm-started from the previous
+/// solution, which is much cheaper than independent fits as solutions change smoothly along
+/// the path. Other arguments are as in [`solve_elastic_net`].
+///
+/// With `early_exit_tol`, the path stops once the relative improvement of the squared error
+/// loss ||y - Xw||^2 between consecutive alphas falls below it, i.e. in the flat tail of the
+/// path where smaller penalties no longer improve the fit. The returned array then holds fewer
+/// rows than there are alphas. Alphas for which all coefficients are still zero never trigger
+/// the early exit.
+///
+/// An empty `alphas` array generates a default grid of 100 alphas, log-spaced from
+/// alpha_max = max_j |x_j^T y| / (n * l1_ratio), the smallest alpha at which all coefficients
+/// are zero, down to 1e-3 * alpha_max. For (near) ridge problems l1_ratio is floored at 1e-3
+/// in this formula. The generated alphas are available from [`default_alpha_grid`].
+#[allow(clippy::too_many_arguments)]
+pub fn solve_elastic_net_path(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    alphas: &Array1<f64>,
+    l1_ratio: Option<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    positive: Option<bool>,
+    early_exit_tol: Option<f64>,
+) -> Array2<f64> {
+    let l1_ratio = l1_ratio.unwrap_or(0.5);
+    let max_iter = max_iter.unwrap_or(1_000);
+    let tol = tol.unwrap_or(0.00001);
+    let positive = positive.unwrap_or(false);
+    assert!(
+        alphas.iter().all(|alpha| *alpha > 0.),
+        "'alphas' must be strictly positive"
+    );
+    assert!(
+        (0. ..=1.).contains(&l1_ratio),
+        "'l1_ratio' must be strictly between 0. and 1."
+    );
+
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let mut alphas = if alphas.is_empty() {
+        default_alpha_grid(y, x, l1_ratio).to_vec()
+    } else {
+        alphas.to_vec()
+    };
+    alphas.sort_by(|a, b| b.total_cmp(a));
+    let xtx_diag = x.map_axis(Axis(0), |xj| xj.dot(&xj));
+
+    let mut path = Array2::<f64>::zeros((alphas.len(), n_features));
+    let mut w = Array1::<f64>::zeros(n_features);
+    let mut loss_prev = y.dot(y);
+    for (i, alpha) in alphas.iter().enumerate() {
+        w = elastic_net_coordinate_descent(
+            y,
+            x,
+            &xtx_diag,
+            w,
+            alpha * n_samples as f64,
+            l1_ratio,
+            max_iter,
+            tol,
+            positive,
+            None,
+            None,
+        );
+        path.row_mut(i).assign(&w);
+        if let Some(early_exit_tol) = early_exit_tol {
+            let residuals = y - &x.dot(&w);
+            let loss = residuals.dot(&residuals);
+            // at the top of the path all coefficients may still be zero, which is not flat
+            let entered = w.iter().any(|w_j| *w_j != 0.);
+            if i > 0 && entered && loss_prev - loss < early_exit_tol * loss_prev {
+                return path.slice(s![..=i, ..]).to_owned();
+            }
+            loss_prev = loss;
+        }
+    }
+    path
+}
+
+/// Computes a weighted elastic net coefficient path over a grid of `alphas` (see
+/// [`solve_elastic_net_path`]) for the loss 1 / (2 * n_samples) * sum_i w_i (y_i - x_i^T b)^2,
+/// with the `sample_weights` w normalized to a mean of one.
+///
+/// The weighted Gram matrix X^T W X and X^T W y are computed once and shared by all alphas, so
+/// the coordinate descent updates are independent of the number of samples. Each fit is
+/// warm-started from the previous (larger) alpha. Rows are ordered from the largest to the
+/// smallest alpha. An empty `alphas` array generates the default grid of
+/// [`solve_elastic_net_path`] from the weighted alpha_max = max_j |x_j^T W y| / (n * l1_ratio).
+#[allow(clippy::too_many_arguments)]
+pub fn solve_elastic_net_path_weighted(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    sample_weights: &Array1<f64>,
+    alphas: &Array1<f64>,
+    l1_ratio: Option<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    positive: Option<bool>,
+) -> Array2<f64> {
+    let l1_ratio = l1_ratio.unwrap_or(0.5);
+    let max_iter = max_iter.unwrap_or(1_000);
+    let tol = tol.unwrap_or(0.00001);
+    let positive = positive.unwrap_or(false);
+    assert!(
+        alphas.iter().all(|alpha| *alpha > 0.),
+        "'alphas' must be strictly positive"
+    );
+    assert!(
+        (0. ..=1.).contains(&l1_ratio),
+        "'l1_ratio' must be strictly between 0. and 1."
+    );
+    assert_eq!(
+        sample_weights.len(),
+        y.len(),
+        "'sample_weights' must have one entry per sample"
+    );
+    assert!(
+        sample_weights.iter().all(|w| *w >= 0.) && sample_weights.sum() > 0.,
+        "'sample_weights' must be non-negative and not all zero"
+    );
+
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let weights = sample_weights / sample_weights.mean().unwrap();
+    let x_weighted = x * &weights.view().insert_axis(Axis(1));
+    let gram = x_weighted.t().dot(x);
+    let x_t_y = x_weighted.t().dot(y);
+
+    let mut alphas = if alphas.is_empty() {
+        // (W X)^T y = X^T W y, so the grid on W X starts from the weighted alpha_max
+        default_alpha_grid(y, &x_weighted, l1_ratio).to_vec()
+    } else {
+        alphas.to_vec()
+    };
+    alphas.sort_by(|a, b| b.total_cmp(a));
+    let mut path = Array2::<f64>::zeros((alphas.len(), n_features));
+    let mut w = Array1::<f64>::zeros(n_features);
+    for (i, alpha) in alphas.iter().enumerate() {
+        let alpha = alpha * n_samples as f64;
+        for _ in 0..max_iter {
+            let w_old = w.clone();
+            for j in 0..n_features {
+                // covariance update: X_j^T W r excluding feature j's own contribution
+                let rho = x_t_y[j] - gram.row(j).dot(&w) + gram[[j, j]] * w[j];
+                w[j] = soft_threshold(&rho, alpha * l1_ratio, positive)
+                    / (gram[[j, j]] + alpha * (1.0 - l1_ratio));
+            }
+            if (&w - &w_old).mapv(|d| d * d).sum().sqrt() < tol {
+                break;
+            }
+        }
+        path.row_mut(i).assign(&w);
+    }
+    path
+}
+
+/// Computes the degrees of freedom at each step of a lasso coefficient path.
+///
+/// For the lasso, the number of non-zero coefficients is an unbiased estimate of the degrees of
+/// freedom (Zou, Hastie & Tibshirani, 2007), which makes it suitable for AIC / BIC based
+/// selection of alpha. Each row of `path` holds the coefficients estimated for one alpha;
+/// coefficients with absolute value not exceeding `tol` (default: 1e-12) are treated as zero.
+pub fn lasso_path_df(path: &Array2<f64>, tol: Option<f64>) -> Array1<usize> {
+    let tol = tol.unwrap_or(1.0e-12);
+    path.axis_iter(Axis(0))
+        .map(|coefficients| coefficients.iter().filter(|w| w.abs() > tol).count())
+        .collect()
+}
+
+pub struct RecursiveLeastSquares {
+    forgetting_factor: f64, // exponential decay factor
+    coef: Array1<f64>,      // coefficient vector
+    p: Array2<f64>,         // state covariance
+    k: Array1<f64>,         // kalman gain
+}
+
+impl RecursiveLeastSquares {
+    pub fn new(
+        num_features: usize,
+        lam: f64,
+        half_life: Option<f64>,
+        initial_state_mean: Option<Array1<f64>>,
+    ) -> Self {
+        // calculate forgetting_factor based on the value of half_life, default to 1.0
+        // (expanding ols)
+        let forgetting_factor = if let Some(half_life) = half_life {
+            (0.5f64.ln() / half_life).exp()
+        } else {
+            1.0
+        };
+
+        let coef = Array1::<f64>::zeros(num_features);
+        let p = Array2::<f64>::eye(num_features) * lam;
+        let k = Array1::<f64>::zeros(num_features);
+        let coef = initial_state_mean.unwrap_or(coef);
+        RecursiveLeastSquares {
+            forgetting_factor,
+            coef,
+            p,
+            k,
+        }
+    }
+
+    pub fn update(&mut self, x: &Array1<f64>, y: f64) {
+        let r = 1.0 + x.t().dot(&self.p).dot(x) / self.forgetting_factor;
+        self.k
+            .assign(&(&self.p.dot(x) / (r * self.forgetting_factor)));
+        let residuals = y - x.dot(&self.coef);
+        self.coef.assign(&(&self.coef + &(&self.k * residuals)));
+        let k_ = &self.k.view().insert_axis(Axis(1)); // K x 1
+        self.p
+            .assign(&(&self.p / self.forgetting_factor - k_.dot(&k_.t()) * r));
+    }
+
+    pub fn predict(&self, x: &Array1<f64>) -> f64 {
+        x.dot(&self.coef)
+    }
+
+    pub fn coefficients(&self) -> &Array1<f64> {
+        &self.coef
+    }
+
+    /// Re-inflates the state covariance to `initial_state_covariance * I`, e.g. after a known
+    /// regime break, keeping the forgetting factor. The coefficients are reset to
+    /// `initial_state_mean` if given, otherwise the current estimates are kept as the prior mean.
+    pub fn reset(
+        &mut self,
+        initial_state_covariance: f64,
+        initial_state_mean: Option<Array1<f64>>,
+    ) {
+        let num_features = self.coef.len();
+        self.p = Array2::<f64>::eye(num_features) * initial_state_covariance;
+        self.k = Array1::<f64>::zeros(num_features);
+        if let Some(initial_state_mean) = initial_state_mean {
+            assert_eq!(
+                initial_state_mean.len(),
+                num_features,
+                "initial_state_mean must have one value per feature"
+            );
+            self.coef = initial_state_mean;
+        }
+    }
+}
+
+/// Online (exponentially weighted) ridge regression.
+///
+/// Unlike [`RecursiveLeastSquares`], whose ridge-like prior given by the initial state covariance
+/// is forgotten along with old samples, this maintains the exact solution of
+/// sum_i forgetting_factor^(t - i) * (y_i - x_i^T w)^2 + alpha * ||w||^2 at every step, i.e. the
+/// L2 penalty is kept at constant strength throughout the stream.
+pub struct RecursiveRidge {
+    forgetting_factor: f64, // exponential decay factor
+    alpha: f64,             // L2 penalty strength
+    coef: Array1<f64>,      // coefficient vector
+    xtx: Array2<f64>,       // exponentially weighted X^T X + alpha I
+    xty: Array1<f64>,       // exponentially weighted X^T y
+}
+
+impl RecursiveRidge {
+    pub fn new(num_features: usize, alpha: f64, half_life: Option<f64>) -> Self {
+        assert!(alpha > 0., "alpha must be strictly positive");
+        // calculate forgetting_factor based on the value of half_life, default to 1.0
+        // (expanding ridge)
+        let forgetting_factor = if let Some(half_life) = half_life {
+            (0.5f64.ln() / half_life).exp()
+        } else {
+            1.0
+        };
+        RecursiveRidge {
+            forgetting_factor,
+            alpha,
+            coef: Array1::<f64>::zeros(num_features),
+            xtx: Array2::<f64>::eye(num_features) * alpha,
+            xty: Array1::<f64>::zeros(num_features),
+        }
+    }
+
+    pub fn update(&mut self, x: &Array1<f64>, y: f64) {
+        let lam = self.forgetting_factor;
+        // decay past information, then re-inject the decayed share of the penalty so that the
+        // effective penalty remains alpha * I
+        self.xtx *= lam;
+        self.xtx += &(outer_product(&x.view(), &x.view()));
+        self.xtx
+            .diag_mut()
+            .mapv_inplace(|v| v + (1. - lam) * self.alpha);
+        self.xty = &self.xty * lam + x * y;
+        self.coef = solve_normal_equations(&self.xtx, &self.xty, true);
+    }
+
+    pub fn predict(&self, x: &Array1<f64>) -> f64 {
+        x.dot(&self.coef)
+    }
+
+    pub fn coefficients(&self) -> &Array1<f64> {
+        &self.coef
+    }
+}
+
+/// Running means, variances and covariances of a stream of feature vectors, accumulated with
+/// Welford's algorithm, e.g. to standardize features online without a second pass.
+#[derive(Clone, Debug)]
+pub struct RunningMoments {
+    count: usize,      // number of observations seen
+    mean: Array1<f64>, // running mean
+    m2: Array2<f64>,   // running sum of outer products of the deviations from the mean
+}
+
+impl RunningMoments {
+    pub fn new(num_features: usize) -> Self {
+        RunningMoments {
+            count: 0,
+            mean: Array1::<f64>::zeros(num_features),
+            m2: Array2::<f64>::zeros((num_features, num_features)),
+        }
+    }
+
+    pub fn update(&mut self, x: &Array1<f64>) {
+        self.count += 1;
+        // multivariate Welford update: M2 += (x - mean_old) (x - mean_new)^T
+        let delta = x - &self.mean;
+        self.mean.scaled_add(1. / self.count as f64, &delta);
+        let delta_new = x - &self.mean;
+        self.m2 += &outer_product(&delta.view(), &delta_new.view());
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> &Array1<f64> {
+        &self.mean
+    }
+
+    /// Covariance matrix with `ddof` delta degrees of freedom (0: population, 1: sample).
+    pub fn covariance(&self, ddof: usize) -> Array2<f64> {
+        assert!(
+            self.count > ddof,
+            "not enough observations for the requested ddof"
+        );
+        &self.m2 / (self.count - ddof) as f64
+    }
+
+    /// Variance of each feature with `ddof` delta degrees of freedom (0: population, 1: sample).
+    pub fn variance(&self, ddof: usize) -> Array1<f64> {
+        self.covariance(ddof).diag().to_owned()
+    }
+
+    /// Standardizes a feature vector with the current means & (population) standard deviations.
+    /// Features without variation so far are only centered.
+    pub fn standardize(&self, x: &Array1<f64>) -> Array1<f64> {
+        let std = self.variance(0).mapv(f64::sqrt);
+        Array1::from_iter((x - &self.mean).iter().zip(std.iter()).map(|(d, s)| {
+            if *s > 0. {
+                d / s
+            } else {
+                *d
+            }
+        }))
+    }
+}
+
+/// Solves an online least squares problem updating coefficients with every sample.
+///
+/// This function performs online least squares regression, updating the coefficients
+/// with every sample. It uses the Recursive Least Squares (RLS) algorithm to adaptively
+/// update the coefficients as new data arrives.
+///
+/// # Arguments
+/// * `y` - A reference to a one-dimensional array containing the target values.
+/// * `x` - A reference to a two-dimensional array containing the input features.
+/// * `half_life` - An optional parameter representing the half-life of forgetting past information
+///   in the Recursive Least Squares algorithm. A smaller half-life places more
+///   weight on recent samples.
+/// * `initial_state_covariance` - An optional parameter representing the initial covariance
+///   matrix of the state estimation. Default value is 10.0.
+/// * `initial_state_mean` - An optional parameter representing the initial mean vector of the
+///   state estimation. If not provided, it is initialized to zeros.
+///
+/// # Returns
+/// A two-dimensional array containing the updated coefficients of the linear regression model.
+pub fn solve_recursive_least_squares(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    half_life: Option<f64>,
+    initial_state_covariance: Option<f64>,
+    initial_state_mean: Option<Array1<f64>>,
+    is_valid: &[bool],
+) -> Array2<f64> {
+    solve_recursive_least_squares_with_predictions(
+        y,
+        x,
+        half_life,
+        initial_state_covariance,
+        initial_state_mean,
+        is_valid,
+    )
+    .0
+}
+
+/// Solves an online least squares problem like [`solve_recursive_least_squares`], additionally
+/// returning the one-step-ahead predictions.
+///
+/// The prediction for sample t is x_t^T w_{t-1}, made with the coefficients *before* they are
+/// updated on (x_t, y_t), i.e. it is a genuine out-of-sample forecast suitable for
+/// backtesting, not the in-sample fitted value x_t^T w_t. The first prediction uses the
+/// initial state mean.
+///
+/// # Returns
+/// A tuple of the coefficients after every sample and the one-step-ahead predictions.
+pub fn solve_recursive_least_squares_with_predictions(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    half_life: Option<f64>,
+    initial_state_covariance: Option<f64>,
+    initial_state_mean: Option<Array1<f64>>,
+    is_valid: &[bool],
+) -> (Array2<f64>, Array1<f64>) {
+    let (coefficients, predictions, _) = run_recursive_least_squares(
+        y,
+        x,
+        half_life,
+        initial_state_covariance,
+        initial_state_mean,
+        is_valid,
+    );
+    (coefficients, predictions)
+}
+
+/// Solves an online least squares problem like [`solve_recursive_least_squares`], additionally
+/// returning the variance of each coefficient after every sample.
+///
+/// RLS tracks the state covariance P, which equals the coefficient covariance up to the noise
+/// variance sigma^2. The returned variances are diag(P) scaled by an exponentially weighted
+/// (with the same forgetting factor) estimate of sigma^2 from the standardized one-step-ahead
+/// errors e_t^2 / (1 + x_t^T P x_t / lambda), each of which has expectation sigma^2. Until a
+/// valid sample has been seen the variances are NaN.
+///
+/// # Returns
+/// A tuple of the coefficients and the coefficient variances, both of shape
+/// (n_samples, n_features).
+pub fn solve_recursive_least_squares_with_variances(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    half_life: Option<f64>,
+    initial_state_covariance: Option<f64>,
+    initial_state_mean: Option<Array1<f64>>,
+    is_valid: &[bool],
+) -> (Array2<f64>, Array2<f64>) {
+    let (coefficients, _, variances) = run_recursive_least_squares(
+        y,
+        x,
+        half_life,
+        initial_state_covariance,
+        initial_state_mean,
+        is_valid,
+    );
+    (coefficients, variances)
+}
+
+/// Runs the RLS recursion, returning the coefficients, one-step-ahead predictions and
+/// coefficient variances after every sample.
+fn run_recursive_least_squares(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    half_life: Option<f64>,
+    initial_state_covariance: Option<f64>,
+    initial_state_mean: Option<Array1<f64>>,
+    is_valid: &[bool],
+) -> (Array2<f64>, Array1<f64>, Array2<f64>) {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let mut recursive_least_squares = RecursiveLeastSquares::new(
+        n_features,
+        initial_state_covariance.unwrap_or(10.0),
+        half_life,
+        initial_state_mean,
+    );
+    let lam = recursive_least_squares.forgetting_factor;
+    let mut predictions = Array1::<f64>::zeros(n_samples);
+    let mut coefficients = Array2::<f64>::zeros((n_samples, n_features));
+    let mut variances = Array2::<f64>::from_elem((n_samples, n_features), f64::NAN);
+    // exponentially weighted sum of standardized squared errors and its total weight
+    let (mut sse, mut weight) = (0., 0.);
+
+    for t in 0..n_samples {
+        let y_t = y[t];
+        let x_t = x.slice(s![t, ..]).to_owned();
+        predictions[t] = recursive_least_squares.predict(&x_t);
+        if is_valid[t] {
+            let p = &recursive_least_squares.p;
+            let r = 1.0 + x_t.dot(&p.dot(&x_t)) / lam;
+            sse = lam * sse + (y_t - predictions[t]).powi(2) / r;
+            weight = lam * weight + 1.;
+            recursive_least_squares.update(&x_t, y_t);
+        }
+        coefficients
+            .slice_mut(s![t, ..])
+            .assign(&recursive_least_squares.coef.view());
+        if weight > 0. {
+            variances
+                .slice_mut(s![t, ..])
+                .assign(&(&recursive_least_squares.p.diag() * (sse / weight)));
+        }
+    }
+    (coefficients, predictions, variances)
+}
+
+/// Solves independent online least squares problems for several targets sharing the same
+/// features (see [`solve_recursive_least_squares`]).
+///
+/// Each column of `y` gets its own [`RecursiveLeastSquares`] state, i.e. its own covariance
+/// matrix P, which is only updated with that target's observations: besides the rows excluded
+/// by `is_valid`, a target skips every row where its value is not finite (e.g. NaN for missing
+/// data), so targets with different missing patterns do not contaminate each other. All targets
+/// are updated time step by time step, sharing each feature row.
+///
+/// # Returns
+/// A three-dimensional array of shape (n_samples, n_targets, n_features) holding the
+/// coefficients of each target after every sample.
+pub fn solve_recursive_least_squares_independent(
+    y: &Array2<f64>,
+    x: &Array2<f64>,
+    half_life: Option<f64>,
+    initial_state_covariance: Option<f64>,
+    initial_state_mean: Option<Array1<f64>>,
+    is_valid: &[bool],
+) -> Array3<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let n_targets = y.shape()[1];
+    assert_eq!(y.shape()[0], n_samples, "y must have one row per sample");
+    let mut states: Vec<RecursiveLeastSquares> = (0..n_targets)
+        .map(|_| {
+            RecursiveLeastSquares::new(
+                n_features,
+                initial_state_covariance.unwrap_or(10.0),
+                half_life,
+                initial_state_mean.clone(),
+            )
+        })
+        .collect();
+    let mut coefficients = Array3::<f64>::zeros((n_samples, n_targets, n_features));
+    for t in 0..n_samples {
+        let x_t = x.row(t).to_owned();
+        for (j, state) in states.iter_mut().enumerate() {
+            let y_tj = y[[t, j]];
+            if is_valid[t] && y_tj.is_finite() {
+                state.update(&x_t, y_tj);
+            }
+            coefficients
+                .slice_mut(s![t, j, ..])
+                .assign(&state.coef.view());
+        }
+    }
+    coefficients
+}
+
+/// Returns a copy of the feature matrix augmented with a leading column of ones (the intercept).
+///
+/// By convention, solvers and diagnostics in this crate which treat an intercept specially
+/// expect it at column index 0.
+pub fn add_intercept(x: &Array2<f64>) -> Array2<f64> {
+    let mut augmented = Array2::<f64>::ones((x.shape()[0], x.shape()[1] + 1));
+    augmented.slice_mut(s![.., 1..]).assign(x);
+    augmented
+}
+
+/// Drops the rows where the target or any feature is NaN or infinite, which [`solve_ols`] and
+/// the other batch solvers would otherwise propagate into all coefficients.
+///
+/// Returns the filtered targets & features together with the validity mask of the original
+/// rows, in the `is_valid` form taken by [`solve_recursive_least_squares`]. Coefficients fit on
+/// the filtered data are indexed by feature and need no mapping back, whereas residuals or
+/// fitted values computed on it belong to the rows flagged valid, in their original order.
+pub fn drop_invalid_rows(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+) -> (Array1<f64>, Array2<f64>, Vec<bool>) {
+    assert_eq!(y.len(), x.shape()[0], "y must have one entry per row of x");
+    let is_valid: Vec<bool> = y
+        .iter()
+        .zip(x.axis_iter(Axis(0)))
+        .map(|(y_i, x_i)| y_i.is_finite() && x_i.iter().all(|v| v.is_finite()))
+        .collect();
+    let rows: Vec<usize> = (0..y.len()).filter(|&i| is_valid[i]).collect();
+    (y.select(Axis(0), &rows), x.select(Axis(0), &rows), is_valid)
+}
+
+/// Replaces the feature matrix with one augmented by a leading column of ones (see
+/// [`add_intercept`]). Note that this still re-allocates as ndarray can not grow in place.
+pub fn add_intercept_inplace(x: &mut Array2<f64>) {
+    *x = add_intercept(x);
+}
+
+/// Fits a continuous piecewise-linear (segmented) regression with a known `breakpoint` on the
+/// feature `threshold_feature`, e.g. for threshold or regime models with a kinked relation.
+///
+/// The hinge basis max(0, x_j - breakpoint) is appended as an extra column and the augmented
+/// design is solved by OLS. The returned k + 1 coefficients are those of the original features
+/// followed by that of the hinge, i.e. the change in the slope of x_j above the breakpoint
+/// (below it the slope is coefficient j, above it coefficient j plus the hinge coefficient).
+pub fn solve_segmented(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    threshold_feature: usize,
+    breakpoint: f64,
+) -> Array1<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    assert!(
+        threshold_feature < n_features,
+        "threshold_feature is out of range"
+    );
+    let mut augmented = Array2::<f64>::zeros((n_samples, n_features + 1));
+    augmented.slice_mut(s![.., ..n_features]).assign(x);
+    augmented.column_mut(n_features).assign(
+        &x.column(threshold_feature)
+            .mapv(|v| (v - breakpoint).max(0.)),
+    );
+    solve_ols(y, &augmented, None, None)
+}
+
+/// Estimates the breakpoint of a segmented regression (see [`solve_segmented`]) by profiling:
+/// each of the `candidate_breakpoints` is fitted and the one minimizing the residual sum of
+/// squares is selected, so the estimate is only as fine as the candidate grid.
+///
+/// # Returns
+///
+/// A tuple of the selected breakpoint and its k + 1 segmented coefficients.
+pub fn solve_segmented_search(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    threshold_feature: usize,
+    candidate_breakpoints: &[f64],
+) -> (f64, Array1<f64>) {
+    assert!(
+        !candidate_breakpoints.is_empty(),
+        "at least one candidate breakpoint is required"
+    );
+    let n_features = x.shape()[1];
+    let (mut best_rss, mut best) = (f64::INFINITY, None);
+    for breakpoint in candidate_breakpoints.iter().copied() {
+        let coefficients = solve_segmented(y, x, threshold_feature, breakpoint);
+        let hinge = x
+            .column(threshold_feature)
+            .mapv(|v| (v - breakpoint).max(0.));
+        let fitted =
+            x.dot(&coefficients.slice(s![..n_features])) + hinge * coefficients[n_features];
+        let residuals = y - &fitted;
+        let rss = residuals.dot(&residuals);
+        if best.is_none() || rss < best_rss {
+            best_rss = rss;
+            best = Some((breakpoint, coefficients));
+        }
+    }
+    let (breakpoint, coefficients) = best.unwrap();
+    (breakpoint, coefficients)
+}
+
+/// Expands a feature matrix into polynomial terms up to the given degree.
+///
+/// Columns are ordered by degree: an optional leading column of ones (`include_bias`), the
+/// original features, then all products of each higher degree in lexicographic order of the
+/// feature indices. With `interaction_only` set, only products of distinct features are emitted
+/// (e.g. x_i * x_j for i < j), so no powers such as x_i^2 appear.
+pub fn polynomial_features(
+    x: &Array2<f64>,
+    degree: usize,
+    interaction_only: bool,
+    include_bias: bool,
+) -> Array2<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let mut terms: Vec<Vec<usize>> = Vec::new();
+    if include_bias {
+        terms.push(vec![]);
+    }
+    // grow each term of the previous degree by a feature index no smaller (or, when
+    // interaction_only is set, strictly larger) than its last one
+    let mut previous: Vec<Vec<usize>> = vec![vec![]];
+    for _ in 0..degree {
+        let mut current = Vec::new();
+        for term in previous.iter() {
+            let start = match term.last() {
+                Some(&last) if interaction_only => last + 1,
+                Some(&last) => last,
+                None => 0,
+            };
+            for j in start..n_features {
+                let mut next = term.clone();
+                next.push(j);
+                current.push(next);
+            }
+        }
+        terms.extend(current.iter().cloned());
+        previous = current;
+    }
+
+    let mut expanded = Array2::<f64>::ones((n_samples, terms.len()));
+    for (k, term) in terms.iter().enumerate() {
+        let mut column = expanded.column_mut(k);
+        for &j in term {
+            column *= &x.column(j);
+        }
+    }
+    expanded
+}
+
+pub fn outer_product(u: &ArrayView1<f64>, v: &ArrayView1<f64>) -> Array2<f64> {
+    // Reshape u and v to have a shape of (n, 1) and (1, m) respectively
+    let u_reshaped = u.insert_axis(Axis(1));
+    let v_reshaped = v.insert_axis(Axis(0));
+
+    // Compute the outer product using broadcasting and dot product
+    u_reshaped.dot(&v_reshaped)
+}
+
+fn inv_diag(c: &Array2<f64>) -> Array2<f64> {
+    let s = c.raw_dim();
+    assert!(s[0] == s[1]);
+    let mut res: Array2<f64> = Array2::zeros(s);
+    for i in 0..s[0] {
+        res[(i, i)] = c[(i, i)].recip();
+    }
+    res
+}
+
+/// Computes the Woodbury update of a matrix A_inv using matrices U, V, B_inv.
+///
+/// The Woodbury update is a method to efficiently update the inverse of a matrix A_inv
+/// when adding or removing rows or columns from the original matrix.
+///
+/// The Woodbury update formula is given by:
+///
+/// ```text
+/// (A + U C V)^{-1} = A^{-1} - A^{-1} U (C^{-1} + V A^{-1} U)^{-1} V A^{-1}
+/// ```
+pub fn woodbury_update(
+    a_inv: &Array2<f64>,
+    u: &Array2<f64>,
+    c: &Array2<f64>,
+    v: &Array2<f64>,
+    c_is_diag: Option<bool>,
+) -> Array2<f64> {
+    // Check if c_is_diag is Some(true)
+    let inv_c = if let Some(true) = c_is_diag {
+        inv_diag(c)
+    } else {
+        inv(c, false, None)
+    }; // r x r
+       // compute V inv(A)
+    let v_inv_a = v.dot(a_inv); // r x K
+    let inv_a_u = a_inv.dot(u); // K x r
+                                // compute term (C^{-1} + V A^{-1} U)^{-1}
+    let intermediate = inv(&(inv_c + v.dot(&inv_a_u)), false, None); // r x r
+    a_inv - inv_a_u.dot(&intermediate).dot(&v_inv_a) // K x K
+}
+
+/// Function to update inv(X^TX) by x_update array of rank r using Woodbury Identity.
+pub fn update_xtx_inv(
+    xtx_inv: &Array2<f64>,
+    x_update: &Array2<f64>,
+    c: Option<&Array2<f64>>,
+) -> Array2<f64> {
+    // Reshape x_new and x_old for Woodbury update
+    let u = x_update.t().to_owned(); // K x r
+    let v = u.t().to_owned(); // r x K
+
+    // let c = Array2::eye(u.shape()[1]); // Identity matrix r x r
+    let default = Array2::eye(u.shape()[1]);
+    let c = c.unwrap_or(&default);
+
+    // Apply Woodbury update
+    woodbury_update(xtx_inv, &u, c, &v, Some(true))
+}
+
+/// Computes the Sherman-Morrison rank one update of a matrix inverse A_inv:
+///
+/// ```text
+/// (A + u v^T)^{-1} = A^{-1} - A^{-1} u v^T A^{-1} / (1 + v^T A^{-1} u)
+/// ```
+///
+/// This is the r = 1 special case of [`woodbury_update`], which avoids forming and inverting
+/// the (1 x 1) intermediate matrices.
+pub fn sherman_morrison_update(
+    a_inv: &Array2<f64>,
+    u: &ArrayView1<f64>,
+    v: &ArrayView1<f64>,
+) -> Array2<f64> {
+    let a_inv_u = a_inv.dot(u); // K
+    let v_a_inv = v.dot(a_inv); // K
+    let denominator = 1. + v.dot(&a_inv_u);
+    a_inv - &(outer_product(&a_inv_u.view(), &v_a_inv.view()) / denominator)
+}
+
+/// State carried between consecutive batches of a rolling least squares fit.
+///
+/// Holds the running X^T X & X^T Y (and inv(X^T X) when propagated with Woodbury) as of the last
+/// observation of a batch, together with the trailing observations which are still inside the
+/// rolling window, so that the next batch can continue the recursion without replaying warm-up.
+#[derive(Clone, Debug)]
+pub struct RollingState {
+    xtx: Array2<f64>,
+    xty: Array1<f64>,
+    xtx_inv: Option<Array2<f64>>,
+    x_window: Array2<f64>, // trailing feature rows still inside the window
+    y_window: Array1<f64>, // trailing targets still inside the window
+    n_seen: usize,         // total number of observations processed so far
+}
+
+/// Solves rolling ordinary least squares (OLS) or, with `alpha > 0`, ridge regression.
+///
+/// This function calculates the coefficients of the linear regression model
+/// using rolling windows. It takes a dependent variable `y`, an independent variable matrix `x`,
+/// the size of the rolling window, the minimum number of periods required to calculate
+/// coefficients, and an optional flag to specify whether to use Woodbury matrix identity
+/// for additional efficiency in case of large number of features.
+///
+/// With a ridge penalty, every window's coefficients solve (X^T X + alpha I) B = X^T y, i.e.
+/// they equal [`solve_ridge`] on that window. The penalty is added once at warm-up and the
+/// window updates only add and remove observations, so both the direct path (which updates
+/// X^T X + alpha I) and the Woodbury path (which propagates inv(X^T X + alpha I) by low rank
+/// updates) keep it at constant strength as the window slides.
+///
+/// # Arguments
+///
+/// * `y` - A reference to a 1-dimensional array representing the dependent variable.
+/// * `x` - A reference to a 2-dimensional array representing the independent variables.
+/// * `window_size` - The size of the rolling window.
+/// * `min_periods` - An optional parameter specifying the minimum number of periods
+///   required to calculate coefficients. If not provided, it defaults to 1.
+/// * `use_woodbury` - An optional parameter specifying whether to use Woodbury matrix identity
+///   which propagates inv(XTX) directly. If not provided, it defaults to `false`.
+/// * `alpha` - An optional L2 penalty strength of rolling ridge regression. If not provided, it
+///   defaults to 0 (OLS).
+/// * `center` - An optional parameter specifying whether to center the window on each index,
+///   i.e. to fit on rows `[i - window_size / 2, i + window_size / 2]`, with the window shrinking
+///   near the boundaries. This looks ahead, so is meant for smoothing rather than forecasting.
+///   If not provided, it defaults to `false` (trailing windows).
+/// * `report_deltas` - An optional parameter specifying whether to return the coefficients as
+///   changes relative to the first (warm-up) fit, e.g. for monitoring coefficient drift. The
+///   first reported row is then zero. If not provided, it defaults to `false`.
+/// * `temporal_alpha` - An optional penalty strength on the change of the coefficients from the
+///   previous window's fit, adding temporal_alpha * ||B_t - B_{t-1}||^2 to each window's
+///   objective (see [`solve_fused_rolling_ridge`]). This smooths the coefficient path. It
+///   requires trailing windows and ignores `use_woodbury`. If not provided, it defaults to 0.
+///
+#[allow(clippy::too_many_arguments)]
+pub fn solve_rolling_ols(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    window_size: usize,
+    min_periods: Option<usize>,
+    use_woodbury: Option<bool>,
+    alpha: Option<f64>,
+    center: Option<bool>,
+    report_deltas: Option<bool>,
+    temporal_alpha: Option<f64>,
+) -> Array2<f64> {
+    let temporal_alpha = temporal_alpha.unwrap_or(0.);
+    let mut coefficients = if temporal_alpha > 0. {
+        assert!(
+            !center.unwrap_or(false),
+            "temporal_alpha requires trailing (not centered) windows"
+        );
+        solve_fused_rolling_ridge(
+            y,
+            x,
+            window_size,
+            min_periods,
+            alpha.unwrap_or(0.),
+            temporal_alpha,
+        )
+    } else if center.unwrap_or(false) {
+        solve_centered_rolling_ols(y, x, window_size, min_periods, use_woodbury, alpha)
+    } else {
+        solve_rolling_ols_with_state(y, x, window_size, min_periods, use_woodbury, alpha, None).0
+    };
+    if report_deltas.unwrap_or(false) {
+        // rows before the first fit are NaN (no estimate), and remain so
+        let first_fit = coefficients
+            .axis_iter(Axis(0))
+            .position(|row| row.iter().all(|c| c.is_finite()));
+        if let Some(first_fit) = first_fit {
+            let reference = coefficients.row(first_fit).to_owned();
+            coefficients -= &reference;
+        }
+    }
+    coefficients
+}
+
+/// Centered rolling OLS built from two trailing passes of width 2 * (window_size / 2) + 1.
+///
+/// The trailing window ending at i + window_size / 2 is exactly the centered window of i (its
+/// expanding warm-up being the shrunk windows near the start). Near the end, the centered
+/// window [i - window_size / 2, n - 1] is the trailing window of a pass over the reversed series.
+fn solve_centered_rolling_ols(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    window_size: usize,
+    min_periods: Option<usize>,
+    use_woodbury: Option<bool>,
+    alpha: Option<f64>,
+) -> Array2<f64> {
+    let n = y.len();
+    let half = window_size / 2;
+    let width = 2 * half + 1;
+    let forward =
+        solve_rolling_ols_with_state(y, x, width, min_periods, use_woodbury, alpha, None).0;
+    let mut coefficients = Array2::from_elem(forward.raw_dim(), f64::NAN);
+    let n_forward = n.saturating_sub(half);
+    coefficients
+        .slice_mut(s![..n_forward, ..])
+        .assign(&forward.slice(s![half.., ..]));
+    if n_forward < n {
+        let y_reversed = y.slice(s![..;-1]).to_owned();
+        let x_reversed = x.slice(s![..;-1, ..]).to_owned();
+        let (backward, _) = solve_rolling_ols_with_state(
+            &y_reversed,
+            &x_reversed,
+            width,
+            min_periods,
+            use_woodbury,
+            alpha,
+            None,
+        );
+        // reversed row r ends the window over original rows [n - 1 - r, n - 1]
+        for i in n_forward..n {
+            let r = n - 1 - (i - half);
+            coefficients.row_mut(i).assign(&backward.row(r));
+        }
+    }
+    coefficients
+}
+
+/// Solves rolling ridge regression with temporal fusion: the coefficients of window t minimize
+/// ||y_t - X_t B||^2 + alpha * ||B||^2 + temporal_alpha * ||B - B_{t-1}||^2, shrinking them
+/// towards the previous window's fit rather than only towards zero.
+///
+/// Given the prior B_{t-1}, this has the closed form
+/// (X_t^T X_t + (alpha + temporal_alpha) I) B = X_t^T y_t + temporal_alpha * B_{t-1}, solved
+/// from the rolling X^T X & X^T Y. The first (warm-up) fit has no prior and is the plain ridge
+/// solution. Larger `temporal_alpha` yields smoother coefficient paths, at the cost of slower
+/// adaptation to genuine changes. Rows with fewer than `min_periods` observations are NaN.
+pub fn solve_fused_rolling_ridge(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    window_size: usize,
+    min_periods: Option<usize>,
+    alpha: f64,
+    temporal_alpha: f64,
+) -> Array2<f64> {
+    assert!(alpha >= 0., "alpha must be non-negative");
+    assert!(temporal_alpha >= 0., "temporal_alpha must be non-negative");
+    let (n, k) = (x.shape()[0], x.shape()[1]);
+    let min_periods = min_periods.unwrap_or(std::cmp::min(k, window_size));
+    let mut coefficients = Array2::from_elem((n, k), f64::NAN);
+    let mut xtx = Array2::<f64>::eye(k) * alpha;
+    let mut xty = Array1::<f64>::zeros(k);
+    let fused = Array2::<f64>::eye(k) * temporal_alpha;
+    let mut previous: Option<Array1<f64>> = None;
+    for i in 0..n {
+        let x_new = x.row(i);
+        xtx += &outer_product(&x_new, &x_new);
+        xty = xty + &x_new * y[i];
+        if i >= window_size {
+            let x_prev = x.row(i - window_size);
+            xtx -= &outer_product(&x_prev, &x_prev);
+            xty = xty - &x_prev * y[i - window_size];
+        }
+        if i + 1 < min_periods {
+            continue;
+        }
+        let coefficients_i = match &previous {
+            Some(prior) => {
+                solve_normal_equations(&(&xtx + &fused), &(&xty + &(prior * temporal_alpha)), true)
+            }
+            None => solve_normal_equations(&xtx, &xty, false),
+        };
+        coefficients.row_mut(i).assign(&coefficients_i);
+        previous = Some(coefficients_i);
+    }
+    coefficients
+}
+
+/// Solves rolling ordinary least squares (OLS) regression over one batch of a chunked stream.
+///
+/// Behaves like [`solve_rolling_ols`], but additionally accepts the [`RollingState`] returned
+/// by the call on the previous batch and returns the state as of the end of this batch. Feeding
+/// consecutive batches through this function produces the same coefficients as a single call
+/// over their concatenation.
+///
+/// # Arguments
+///
+/// * `state` - An optional state returned from the previous batch. If not provided, the batch
+///   is treated as the start of the series and the usual warm-up is performed.
+///
+/// # Returns
+///
+/// A tuple of the coefficients for the rows of this batch and the state to pass to the next one.
+#[allow(clippy::too_many_arguments)]
+pub fn solve_rolling_ols_with_state(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    window_size: usize,
+    min_periods: Option<usize>,
+    use_woodbury: Option<bool>,
+    alpha: Option<f64>,
+    state: Option<RollingState>,
+) -> (Array2<f64>, RollingState) {
+    let k = x.shape()[1]; // Number of independent variables
+    let min_periods = min_periods.unwrap_or(std::cmp::min(k, window_size));
+    // default to using woodbury if number of features is relatively large.
+    let use_woodbury = use_woodbury.unwrap_or(k > 60);
+    let alpha = alpha.unwrap_or(0.0);
+
+    // we allow the user to pass a min_periods < k, but this may result in
+    // unstable warm-up coefficients - so warn the user.
+    if !(min_periods >= k && min_periods <= window_size) {
+        println!(
+            "warning: min_periods should be greater or equal to the number of regressors \
+                  in the model and less than or equal to the window size otherwise \
+                  estimated parameters may be unstable!"
+        )
+    };
+
+    // prepend the trailing window carried over from the previous batch. If warm-up had not yet
+    // completed, every observation seen so far is carried and the warm-up is simply replayed.
+    let (x, y, n_carried, n_offset, carried) = match state {
+        Some(state) => {
+            let n_carried = state.y_window.len();
+            let x_full = ndarray::concatenate(Axis(0), &[state.x_window.view(), x.view()])
+                .expect("carried state must have the same number of features as the batch");
+            let y_full = ndarray::concatenate(Axis(0), &[state.y_window.view(), y.view()]).unwrap();
+            let (n_offset, carried) = if state.n_seen >= min_periods {
+                let carried = Some((state.xtx, state.xty, state.xtx_inv));
+                (state.n_seen - n_carried, carried)
+            } else {
+                (0, None)
+            };
+            let (x_full, y_full) = (CowArray::from(x_full), CowArray::from(y_full));
+            (x_full, y_full, n_carried, n_offset, carried)
+        }
+        None => (
+            CowArray::from(x.view()),
+            CowArray::from(y.view()),
+            0,
+            0,
+            None,
+        ),
+    };
+    let n = x.shape()[0];
+    let mut coefficients = Array2::from_elem((n, k), f64::NAN);
+
+    // Initialize X^T X, inv(X.T X), and X^T Y (either from carried state or from warm-up)
+    let is_warm = carried.is_some();
+    let (mut xtx, mut xty, xtx_inv, start) = if let Some((xtx, xty, xtx_inv)) = carried {
+        (xtx, xty, xtx_inv, n_carried)
+    } else {
+        let n_warmup = min_periods.min(n);
+        let x_warmup = x.slice(s![..n_warmup, ..]);
+        let y_warmup = y.slice(s![..n_warmup]);
+        let xty = x_warmup.t().dot(&y_warmup);
+        let mut xtx = x_warmup.t().dot(&x_warmup);
+
+        // add ridge penalty: window updates below only add or remove observations, so it
+        // persists unchanged in X^T X (and in inv(X^T X) when propagated with Woodbury)
+        if alpha > 0. {
+            xtx = xtx + Array2::<f64>::eye(k) * alpha
+        }
+        (xtx, xty, None, min_periods)
+    };
+    let ready = is_warm || n >= min_periods;
+
+    // Use woodbury to propagate inv(X.T X) & (X.T Y)
+    let xtx_inv = if use_woodbury && ready {
+        let mut xtx_inv = xtx_inv.unwrap_or_else(|| inv(&xtx, false, None));
+        if !is_warm {
+            // assign warm-up coefficients
+            let coef_warmup = xtx_inv.t().dot(&xty);
+            coefficients
+                .slice_mut(s![min_periods - 1, ..])
+                .assign(&coef_warmup);
+        }
+
+        // make c [[-1, 0], [0, 1]]; which drops old and adds new
+        let c: Array2<f64> = array![[-1., 0.], [0., 1.]];
+
+        // Slide the window and update coefficients
+        for i in start..n {
+            let i_start = i.saturating_sub(window_size);
+            let x_new = x.row(i);
+
+            if i + n_offset > window_size - 1 {
+                let x_prev = x.row(i_start);
+
+                // create rank 2 update array
+                let mut x_update = ndarray::stack(Axis(0), &[x_prev, x_new]).unwrap(); // 2 x K
+
+                // multiply x_old row by -1.0 (subtract the previous contribution)
+                x_update.row_mut(0).mapv_inplace(|elem| -elem);
+
+                // update inv(XTX) and XTY
+                xtx_inv = update_xtx_inv(&xtx_inv, &x_update, Some(&c));
+                xty = xty + &x_new * y[i]  // add new contribution
+                    - &x_prev * y[i_start] // subtract old contribution
+                ;
+            } else {
+                // rank 1 update while the window is still filling up
+                xtx_inv = sherman_morrison_update(&xtx_inv, &x_new, &x_new);
+                xty = xty + &x_new * y[i];
+            }
+            coefficients.slice_mut(s![i, ..]).assign(&xtx_inv.dot(&xty));
+        }
+        Some(xtx_inv)
+    } else if ready {
+        // update X.T X & X.T Y and solve normal equations at every time step
+        if !is_warm {
+            // assign warm-up coefficients
+            let coef_warmup = solve_normal_equations(&xtx, &xty, false);
+
+            coefficients
+                .slice_mut(s![min_periods - 1, ..])
+                .assign(&coef_warmup);
+        }
+
+        // Slide the window and update coefficients
+        for i in start..n {
+            let i_start = i.saturating_sub(window_size);
+            // update XTX w/ latest data point
+            let x_new = x.row(i);
+
+            // Add new contributions
+            xtx += &outer_product(&x_new, &x_new);
+            xty = xty + &x_new * y[i];
+
+            // Subtract the previous contribution
+            if i + n_offset > window_size - 1 {
+                let x_prev = x.row(i_start);
+                xtx -= &outer_product(&x_prev, &x_prev);
+                xty = xty - &x_prev * y[i_start];
+            }
+
+            // update coefficients
+            let coefficients_i = solve_normal_equations(&xtx, &xty, true);
+            coefficients.slice_mut(s![i, ..]).assign(&coefficients_i);
+        }
+        None
+    } else {
+        None
+    };
+
+    // carry the trailing observations which may still be needed by the next batch
+    let n_keep = n.min(max(window_size, min_periods));
+    let x_window = x.slice(s![n - n_keep.., ..]).to_owned();
+    let y_window = y.slice(s![n - n_keep..]).to_owned();
+    if xtx_inv.is_some() {
+        // X^T X is not propagated alongside its inverse, so rebuild it from the current window
+        let x_last = x_window.slice(s![n_keep - n_keep.min(window_size).., ..]);
+        xtx = x_last.t().dot(&x_last) + Array2::<f64>::eye(k) * alpha;
+    }
+    let state = RollingState {
+        xtx,
+        xty,
+        xtx_inv,
+        x_window,
+        y_window,
+        n_seen: n_offset + n,
+    };
+    (coefficients.slice(s![n_carried.., ..]).to_owned(), state)
+}
+
+/// Computes the effective degrees of freedom trace(H) = sum_j s_j^2 / (s_j^2 + alpha) of a
+/// rolling ridge fit (see [`solve_rolling_ols`] with `alpha`) for every window, where s_j are
+/// the singular values of the window's features, obtained from the eigenvalues of its X^T X.
+///
+/// It approaches k in well-conditioned windows and drops where the features are (nearly)
+/// collinear or have little variation, showing how much the penalty constrains each fit. Windows
+/// grow up to `window_size` rows like those of the rolling solver; rows with fewer than
+/// `min_periods` observations (defaulting to min(k, window_size)) are NaN.
+pub fn rolling_effective_df(
+    x: &Array2<f64>,
+    window_size: usize,
+    min_periods: Option<usize>,
+    alpha: f64,
+) -> Array1<f64> {
+    assert!(alpha >= 0., "alpha must be non-negative");
+    let (n, k) = (x.shape()[0], x.shape()[1]);
+    let min_periods = min_periods.unwrap_or(std::cmp::min(k, window_size));
+    let mut effective_df = Array1::from_elem(n, f64::NAN);
+    let mut xtx = Array2::<f64>::zeros((k, k));
+    for i in 0..n {
+        xtx += &outer_product(&x.row(i), &x.row(i));
+        if i >= window_size {
+            let x_old = x.row(i - window_size);
+            xtx -= &outer_product(&x_old, &x_old);
+        }
+        if i + 1 >= min_periods {
+            let eigenvalues = xtx.view().into_faer().selfadjoint_eigenvalues(Side::Lower);
+            effective_df[i] = eigenvalues
+                .iter()
+                .map(|lambda| {
+                    // clip round-off from the rolling updates
+                    let s2 = lambda.max(0.);
+                    if s2 + alpha > 0. {
+                        s2 / (s2 + alpha)
+                    } else {
+                        0.
+                    }
+                })
+                .sum();
+        }
+    }
+    effective_df
+}
+
+/// Solves rolling elastic net (or, with `l1_ratio = 1`, lasso) regression for time-varying
+/// sparse coefficients.
+///
+/// Every window's coefficients solve the problem of [`solve_elastic_net`] on its (up to)
+/// `window_size` rows, with the penalty scaled by the window length. Since adjacent windows
+/// overlap heavily, the coordinate descent of each window is warm-started from the previous
+/// window's solution, which usually converges in a few sweeps. Rows with fewer than
+/// `min_periods` observations (defaulting to min(k, window_size)) are NaN, matching
+/// [`solve_rolling_ols`].
+#[allow(clippy::too_many_arguments)]
+pub fn solve_rolling_elastic_net(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    window_size: usize,
+    min_periods: Option<usize>,
+    alpha: f64,
+    l1_ratio: Option<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    positive: Option<bool>,
+) -> Array2<f64> {
+    let (n, k) = (x.shape()[0], x.shape()[1]);
+    let min_periods = min_periods.unwrap_or(std::cmp::min(k, window_size));
+    let l1_ratio = l1_ratio.unwrap_or(0.5);
+    let max_iter = max_iter.unwrap_or(1_000);
+    let tol = tol.unwrap_or(0.00001);
+    let positive = positive.unwrap_or(false);
+    assert!(alpha > 0., "'alpha' must be strictly positive");
+    assert!(
+        (0. ..=1.).contains(&l1_ratio),
+        "'l1_ratio' must be strictly between 0. and 1."
+    );
+    assert!(
+        min_periods >= 1 && min_periods <= window_size,
+        "min_periods must be between 1 and the window size"
+    );
+
+    let mut coefficients = Array2::from_elem((n, k), f64::NAN);
+    let mut w = Array1::<f64>::zeros(k);
+    for i in (min_periods - 1)..n {
+        let start = (i + 1).saturating_sub(window_size);
+        let x_window = x.slice(s![start..=i, ..]).to_owned();
+        let y_window = y.slice(s![start..=i]).to_owned();
+        let xtx_diag = x_window.map_axis(Axis(0), |xj| xj.dot(&xj));
+        // warm start from the previous window's solution
+        w = elastic_net_coordinate_descent(
+            &y_window,
+            &x_window,
+            &xtx_diag,
+            w,
+            alpha * (i + 1 - start) as f64,
+            l1_ratio,
+            max_iter,
+            tol,
+            positive,
+            None,
+            None,
+        );
+        coefficients.row_mut(i).assign(&w);
+    }
+    coefficients
+}
+
+/// Computes rolling one-step-ahead forecasts together with their prediction intervals.
+///
+/// The forecast for row i uses the coefficients fit on the (up to) `window_size` rows preceding
+/// it, so it never looks at y[i]. The interval is x_i^T B +/- t * sqrt(s^2 * (1 + h)), where s^2
+/// is the residual variance of that window, h = x_i^T inv(X^T X) x_i the leverage of the
+/// forecast point and t the Student's t quantile with (m - k) degrees of freedom for a window
+/// of m rows.
+///
+/// # Arguments
+///
+/// * `min_periods` - An optional minimum number of preceding rows required to forecast. It
+///   defaults to k + 1 and must exceed k, so the residual variance is defined.
+/// * `level` - An optional confidence level of the interval. It defaults to 0.95.
+///
+/// # Returns
+///
+/// A tuple of (predictions, lower bounds, upper bounds); rows without enough history are NaN.
+pub fn rolling_prediction_intervals(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    window_size: usize,
+    min_periods: Option<usize>,
+    level: Option<f64>,
+) -> (Array1<f64>, Array1<f64>, Array1<f64>) {
+    let (n, k) = (x.shape()[0], x.shape()[1]);
+    let min_periods = min_periods.unwrap_or(k + 1);
+    let level = level.unwrap_or(0.95);
+    assert!(
+        min_periods > k,
+        "min_periods must exceed the number of features"
+    );
+    assert!(
+        min_periods <= window_size,
+        "min_periods can not exceed the window size"
+    );
+    assert!(
+        level > 0. && level < 1.,
+        "level must be strictly between 0 and 1"
+    );
+
+    let mut predictions = Array1::from_elem(n, f64::NAN);
+    let mut lower = Array1::from_elem(n, f64::NAN);
+    let mut upper = Array1::from_elem(n, f64::NAN);
+
+    // X^T X, X^T Y & Y^T Y of the window of rows preceding i
+    let mut xtx = Array2::<f64>::zeros((k, k));
+    let mut xty = Array1::<f64>::zeros(k);
+    let mut yty = 0.;
+    for i in 0..n {
+        let m = i.min(window_size);
+        if m >= min_periods {
+            let xtx_inv = inv(&xtx, true, None);
+            let coefficients = xtx_inv.dot(&xty);
+            // RSS = Y^T Y - B^T X^T Y at the least squares solution
+            let sigma2 = (yty - coefficients.dot(&xty)).max(0.) / (m - k) as f64;
+            let x_i = x.row(i);
+            let se = (sigma2 * (1. + x_i.dot(&xtx_inv.dot(&x_i)))).sqrt();
+            let t = student_t_ppf(0.5 + 0.5 * level, (m - k) as f64);
+            predictions[i] = x_i.dot(&coefficients);
+            lower[i] = predictions[i] - t * se;
+            upper[i] = predictions[i] + t * se;
+        }
+
+        // slide the window to end at row i
+        let x_new = x.row(i);
+        xtx += &outer_product(&x_new, &x_new);
+        xty = xty + &x_new * y[i];
+        yty += y[i] * y[i];
+        if i >= window_size {
+            let x_prev = x.row(i - window_size);
+            xtx -= &outer_product(&x_prev, &x_prev);
+            xty = xty - &x_prev * y[i - window_size];
+            yty -= y[i - window_size] * y[i - window_size];
+        }
+    }
+    (predictions, lower, upper)
+}
+
+/// Linearly interpolated quantile of an already sorted (ascending) slice.
+fn sorted_quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q.clamp(0., 1.) * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// Computes the classical (homoskedastic) covariance matrix of the OLS coefficients
+/// sigma^2 * inv(X^T X), with sigma^2 = RSS / dof for the residual degrees of freedom `dof`
+/// (n - k for OLS, see [`degrees_of_freedom`]).
+pub fn ols_coef_covariance(x: &Array2<f64>, residuals: &Array1<f64>, dof: usize) -> Array2<f64> {
+    assert!(dof > 0, "the residual degrees of freedom must be positive");
+    let sigma2 = residuals.dot(residuals) / dof as f64;
+    inv(&x.t().dot(x), true, None) * sigma2
+}
+
+/// Computes the classical (homoskedastic) OLS standard errors sqrt(diag(sigma^2 * inv(X^T X))),
+/// with sigma^2 = RSS / (n - k), i.e. the square root of the diagonal of
+/// [`ols_coef_covariance`].
+///
+/// By default inv(X^T X) is formed from the normal equations, which squares the condition
+/// number of X. With `qr_based` it is instead obtained from the R factor of the column-pivoted
+/// QR of X (see [`compute_qr`]) as inv(R) inv(R)^T, which is more accurate for ill-conditioned
+/// designs.
+pub fn ols_standard_errors(
+    x: &Array2<f64>,
+    residuals: &Array1<f64>,
+    qr_based: Option<bool>,
+) -> Array1<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    if !qr_based.unwrap_or(false) {
+        return ols_coef_covariance(x, residuals, n_samples - n_features)
+            .diag()
+            .mapv(f64::sqrt);
+    }
+    let sigma2 = residuals.dot(residuals) / (n_samples - n_features) as f64;
+    let qr = compute_qr(x);
+    let r_inv = upper_triangular_inverse(&qr.r);
+    // diag(inv(R) inv(R)^T) holds the squared row norms of inv(R), in pivoted order
+    let mut standard_errors = Array1::<f64>::zeros(n_features);
+    for (j, &p) in qr.permutation.iter().enumerate() {
+        standard_errors[p] = (r_inv.row(j).dot(&r_inv.row(j)) * sigma2).sqrt();
+    }
+    standard_errors
+}
+
+/// Inverts an upper triangular matrix by back substitution.
+fn upper_triangular_inverse(r: &Array2<f64>) -> Array2<f64> {
+    let k = r.shape()[0];
+    let mut r_inv = Array2::<f64>::zeros((k, k));
+    for j in 0..k {
+        r_inv[[j, j]] = 1. / r[[j, j]];
+        for i in (0..j).rev() {
+            let sum: f64 = (i + 1..=j).map(|l| r[[i, l]] * r_inv[[l, j]]).sum();
+            r_inv[[i, j]] = -sum / r[[i, i]];
+        }
+    }
+    r_inv
+}
+
+/// Computes studentized (bootstrap-t) confidence intervals for OLS coefficients.
+///
+/// Observations are resampled with replacement (pairs bootstrap). Within each resample the
+/// coefficients and their standard errors are re-estimated to form the pivotal statistic
+/// t* = (b* - b) / se*, and the interval is obtained by inverting its bootstrap distribution:
+/// [b - q(1 - a/2) * se, b - q(a/2) * se], where `a = 1 - level`.
+///
+/// # Arguments
+///
+/// * `y` - Target vector.
+/// * `x` - Feature matrix.
+/// * `n_boot` - Number of bootstrap resamples.
+/// * `level` - Confidence level of the intervals, e.g. 0.95.
+/// * `seed` - Optional seed of the random number generator, for reproducibility.
+///
+/// # Returns
+///
+/// A tuple of the (lower, upper) interval bounds for each coefficient. The bounds are NaN for
+/// coefficients with fewer than two resamples giving a finite t*, e.g. when the resampled
+/// standard errors vanish for (near) exact fits.
+pub fn bootstrap_t_ci(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    n_boot: usize,
+    level: f64,
+    seed: Option<u64>,
+) -> (Array1<f64>, Array1<f64>) {
+    assert!(
+        level > 0. && level < 1.,
+        "'level' must be strictly between 0. and 1."
+    );
+    assert!(n_boot > 1, "'n_boot' must be greater than 1");
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let coefficients = solve_ols(y, x, None, None);
+    let std_errors = ols_standard_errors(x, &(y - &x.dot(&coefficients)), None);
+
+    // bootstrap distribution of the pivotal t-statistic, for each coefficient
+    let mut t_stats = Array2::<f64>::zeros((n_boot, n_features));
+    for mut t_b in t_stats.axis_iter_mut(Axis(0)) {
+        let indices: Vec<usize> = (0..n_samples)
+            .map(|_| rng.gen_range(0..n_samples))
+            .collect();
+        let x_b = x.select(Axis(0), &indices);
+        let y_b = y.select(Axis(0), &indices);
+        let coefficients_b = solve_ols(&y_b, &x_b, None, None);
+        let std_errors_b = ols_standard_errors(&x_b, &(&y_b - &x_b.dot(&coefficients_b)), None);
+        t_b.assign(&((&coefficients_b - &coefficients) / &std_errors_b));
+    }
+
+    let alpha = 1. - level;
+    let mut lower = Array1::<f64>::zeros(n_features);
+    let mut upper = Array1::<f64>::zeros(n_features);
+    for j in 0..n_features {
+        // drop degenerate resamples (e.g. with a singular design) before inverting
+        let mut t_j: Vec<f64> = t_stats
+            .column(j)
+            .iter()
+            .copied()
+            .filter(|t| t.is_finite())
+            .collect();
+        if t_j.len() < 2 {
+            (lower[j], upper[j]) = (f64::NAN, f64::NAN);
+            continue;
+        }
+        t_j.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        lower[j] = coefficients[j] - sorted_quantile(&t_j, 1. - alpha / 2.) * std_errors[j];
+        upper[j] = coefficients[j] - sorted_quantile(&t_j, alpha / 2.) * std_errors[j];
+    }
+    (lower, upper)
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation, g = 7).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // reflection formula
+        std::f64::consts::PI.ln() - (std::f64::consts::PI * x).sin().ln() - ln_gamma(1. - x)
+    } else {
+        let x = x - 1.;
+        let t = x + 7.5;
+        let series = COEFFICIENTS[1..]
+            .iter()
+            .enumerate()
+            .fold(COEFFICIENTS[0], |acc, (i, c)| {
+                acc + c / (x + (i + 1) as f64)
+            });
+        0.5 * (2. * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+    }
+}
+
+/// Regularized upper incomplete gamma function Q(a, x) = 1 - P(a, x).
+fn gamma_q(a: f64, x: f64) -> f64 {
+    const MAX_ITER: usize = 500;
+    const EPS: f64 = 1.0e-15;
+    if x <= 0. {
+        return 1.;
+    }
+    let ln_prefactor = a * x.ln() - x - ln_gamma(a);
+    if x < a + 1. {
+        // series representation of P(a, x)
+        let (mut term, mut sum, mut ap) = (1. / a, 1. / a, a);
+        for _ in 0..MAX_ITER {
+            ap += 1.;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * EPS {
+                break;
+            }
+        }
+        1. - sum * ln_prefactor.exp()
+    } else {
+        // continued fraction representation of Q(a, x) (modified Lentz)
+        let tiny = f64::MIN_POSITIVE / EPS;
+        let mut b = x + 1. - a;
+        let mut c = 1. / tiny;
+        let mut d = 1. / b;
+        let mut h = d;
+        for i in 1..MAX_ITER {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1. / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.).abs() < EPS {
+                break;
+            }
+        }
+        ln_prefactor.exp() * h
+    }
+}
+
+/// Survival function (1 - CDF) of the chi-squared distribution with `df` degrees of freedom.
+fn chi2_sf(x: f64, df: f64) -> f64 {
+    gamma_q(0.5 * df, 0.5 * x)
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+fn beta_inc(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0. {
+        return 0.;
+    }
+    if x >= 1. {
+        return 1.;
+    }
+    let ln_prefactor = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1. - x).ln();
+    // the continued fraction converges quickly for x < (a + 1) / (a + b + 2), use symmetry otherwise
+    if x < (a + 1.) / (a + b + 2.) {
+        ln_prefactor.exp() * beta_continued_fraction(a, b, x) / a
+    } else {
+        1. - ln_prefactor.exp() * beta_continued_fraction(b, a, 1. - x) / b
+    }
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz).
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITER: usize = 500;
+    const EPS: f64 = 1.0e-15;
+    let tiny = f64::MIN_POSITIVE / EPS;
+    let mut c = 1.;
+    let mut d = 1. - (a + b) * x / (a + 1.);
+    if d.abs() < tiny {
+        d = tiny;
+    }
+    d = 1. / d;
+    let mut h = d;
+    for m in 1..MAX_ITER {
+        let m = m as f64;
+        // even and odd steps of the continued fraction
+        for an in [
+            m * (b - m) * x / ((a + 2. * m - 1.) * (a + 2. * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2. * m) * (a + 2. * m + 1.)),
+        ] {
+            d = 1. + an * d;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = 1. + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1. / d;
+            h *= d * c;
+        }
+        if (d * c - 1.).abs() < EPS {
+            break;
+        }
+    }
+    h
+}
+
+/// Cumulative distribution function of Student's t distribution with `df` degrees of freedom.
+fn student_t_cdf(t: f64, df: f64) -> f64 {
+    let tail = 0.5 * beta_inc(0.5 * df, 0.5, df / (df + t * t));
+    if t > 0. {
+        1. - tail
+    } else {
+        tail
+    }
+}
+
+/// Survival function (1 - CDF) of the F distribution with (`df1`, `df2`) degrees of freedom.
+fn f_sf(f: f64, df1: f64, df2: f64) -> f64 {
+    if f <= 0. {
+        return 1.;
+    }
+    beta_inc(0.5 * df2, 0.5 * df1, df2 / (df2 + df1 * f))
+}
+
+/// Quantile function of Student's t distribution with `df` degrees of freedom (by bisection).
+fn student_t_ppf(p: f64, df: f64) -> f64 {
+    assert!(
+        p > 0. && p < 1.,
+        "probability must be strictly between 0 and 1"
+    );
+    let (mut lo, mut hi) = (-1., 1.);
+    while student_t_cdf(lo, df) > p {
+        lo *= 2.;
+    }
+    while student_t_cdf(hi, df) < p {
+        hi *= 2.;
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if student_t_cdf(mid, df) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+        if hi - lo < 1e-12 * (1. + mid.abs()) {
+            break;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// Computes the leverage (diagonal of the hat matrix H = X inv(X^T X) X^T) of each observation.
+pub fn leverage(x: &Array2<f64>) -> Array1<f64> {
+    let xtx_inv = inv(&x.t().dot(x), true, None);
+    (&x.dot(&xtx_inv) * x).sum_axis(Axis(1))
+}
+
+/// Computes the leverage of each observation as [`leverage`], but from the thin QR factor of X
+/// (see [`compute_qr`]): as H = Q Q^T, h_ii is the squared norm of the i-th row of Q. This
+/// avoids inverting X^T X, which squares the condition number. X must have full column rank.
+pub fn leverage_qr(x: &Array2<f64>) -> Array1<f64> {
+    let q = compute_qr(x).q;
+    (&q * &q).sum_axis(Axis(1))
+}
+
+/// Computes the leave-one-out cross-validation error of OLS as the PRESS statistic
+/// sum_i (e_i / (1 - h_ii))^2, where e_i are the in-sample residuals and h_ii the leverages
+/// (see [`leverage_qr`]). This closed form is exact for linear regression, so the n refits
+/// are never performed; divide by n for the LOOCV mean squared error.
+pub fn ols_loocv(y: &Array1<f64>, x: &Array2<f64>, solve_method: Option<SolveMethod>) -> f64 {
+    let coefficients = solve_ols(y, x, solve_method, None);
+    let residuals = y - &x.dot(&coefficients);
+    let h = leverage_qr(x);
+    residuals
+        .iter()
+        .zip(h.iter())
+        .map(|(e, h)| (e / (1. - h)).powi(2))
+        .sum()
+}
+
+/// Flavours of heteroskedasticity-consistent (sandwich) covariance estimators.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CovarianceType {
+    HC0, // White (1980)
+    HC1, // HC0 with a small sample n / (n - k) correction
+    HC2, // residuals scaled by 1 / (1 - h_ii)
+    HC3, // residuals scaled by 1 / (1 - h_ii)^2, approximately the jackknife
+}
+
+/// Computes a heteroskedasticity-robust (sandwich) covariance matrix of OLS coefficients:
+/// inv(X^T X) X^T diag(omega) X inv(X^T X), with omega depending on `covariance_type`.
+pub fn ols_robust_covariance(
+    x: &Array2<f64>,
+    residuals: &Array1<f64>,
+    covariance_type: CovarianceType,
+) -> Array2<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let xtx_inv = inv(&x.t().dot(x), true, None);
+    let squared_residuals = residuals.mapv(|r| r * r);
+    let omega = match covariance_type {
+        CovarianceType::HC0 => squared_residuals,
+        CovarianceType::HC1 => {
+            squared_residuals * (n_samples as f64 / (n_samples - n_features) as f64)
+        }
+        CovarianceType::HC2 => squared_residuals / leverage(x).mapv(|h| 1. - h),
+        CovarianceType::HC3 => squared_residuals / leverage(x).mapv(|h| (1. - h).powi(2)),
+    };
+    let meat = x.t().dot(&(x * &omega.insert_axis(Axis(1))));
+    xtx_inv.dot(&meat).dot(&xtx_inv)
+}
+
+/// Computes the efficiency loss of OLS relative to GLS under a known error covariance `omega`:
+/// the ratio of each OLS coefficient variance, diag(inv(X^T X) X^T Omega X inv(X^T X)), to the
+/// corresponding GLS variance, diag(inv(X^T inv(Omega) X)).
+///
+/// By the Gauss-Markov theorem GLS is the best linear unbiased estimator, so the ratios are at
+/// least one, with equality for spherical errors (Omega proportional to I, where OLS is BLUE).
+/// Large ratios indicate that modelling the error covariance is worth the trouble.
+pub fn ols_vs_gls_efficiency(x: &Array2<f64>, omega: &Array2<f64>) -> Array1<f64> {
+    let n_samples = x.shape()[0];
+    assert_eq!(
+        omega.shape(),
+        &[n_samples, n_samples],
+        "omega must be an n_samples x n_samples matrix"
+    );
+    let xtx_inv = inv(&x.t().dot(x), true, None);
+    let ols_covariance = xtx_inv.dot(&x.t().dot(omega).dot(x)).dot(&xtx_inv);
+    let omega_inv = inv(omega, true, None);
+    let gls_covariance = inv(&x.t().dot(&omega_inv).dot(x), true, None);
+    ols_covariance.diag().to_owned() / gls_covariance.diag()
+}
+
+/// Computes the covariance inv(X^T inv(Omega) X) of the GLS coefficients under the (known or,
+/// for FGLS, estimated) error covariance `omega`, which replaces the OLS form
+/// sigma^2 inv(X^T X) (see [`ols_coef_covariance`]) for inference after a GLS fit.
+///
+/// inv(Omega) X is obtained from the Cholesky factorization of Omega rather than by inverting
+/// it, so Omega must be symmetric positive definite. For Omega = sigma^2 I this reduces to the
+/// OLS covariance with a known sigma^2.
+pub fn gls_coef_covariance(x: &Array2<f64>, omega: &Array2<f64>) -> Array2<f64> {
+    let n_samples = x.shape()[0];
+    assert_eq!(
+        omega.shape(),
+        &[n_samples, n_samples],
+        "omega must be an n_samples x n_samples matrix"
+    );
+    let omega_inv_x = omega
+        .view()
+        .into_faer()
+        .cholesky(Side::Lower)
+        .unwrap_or_else(|_| panic!("omega must be symmetric positive definite"))
+        .solve(x.view().into_faer())
+        .as_ref()
+        .into_ndarray()
+        .to_owned();
+    inv(&x.t().dot(&omega_inv_x), true, None)
+}
+
+/// Wald test of the joint hypothesis that a subset of coefficients are all zero.
+///
+/// Computes the statistic b_S^T inv(Cov_SS) b_S, which is asymptotically chi-squared with
+/// |S| degrees of freedom. Passing a robust covariance (e.g. from [`ols_robust_covariance`])
+/// makes the test heteroskedasticity-robust.
+///
+/// # Arguments
+///
+/// * `coefficients` - Estimated coefficients.
+/// * `cov` - Covariance matrix of the estimated coefficients.
+/// * `indices` - Indices S of the coefficients under test.
+///
+/// # Returns
+///
+/// A tuple of the chi-squared statistic, its degrees of freedom, and the p-value.
+pub fn wald_test(
+    coefficients: &Array1<f64>,
+    cov: &Array2<f64>,
+    indices: &[usize],
+) -> (f64, usize, f64) {
+    assert!(!indices.is_empty(), "must test at least one coefficient");
+    let b_s = coefficients.select(Axis(0), indices);
+    let cov_ss = cov.select(Axis(0), indices).select(Axis(1), indices);
+    let chi2 = b_s.dot(&solve_normal_equations(&cov_ss, &b_s, true));
+    let df = indices.len();
+    (chi2, df, chi2_sf(chi2, df as f64))
+}
+
+/// Tests the overall significance of a regression: that all coefficients except the intercept
+/// are jointly zero, using a Wald test (see [`wald_test`]) with the covariance `robust_cov`.
+///
+/// With a heteroskedasticity-robust covariance (e.g. from [`ols_robust_covariance`]) this is the
+/// robust counterpart of the classical overall F-test. With the classical covariance
+/// ([`ols_coef_covariance`]) the statistic equals q * F for q tested slopes. Without an
+/// `intercept_index` all coefficients are tested.
+///
+/// # Returns
+///
+/// A tuple of the chi-squared statistic, its degrees of freedom, and the p-value.
+pub fn overall_significance_robust(
+    coefficients: &Array1<f64>,
+    robust_cov: &Array2<f64>,
+    intercept_index: Option<usize>,
+) -> (f64, usize, f64) {
+    let slopes: Vec<usize> = (0..coefficients.len())
+        .filter(|&j| Some(j) != intercept_index)
+        .collect();
+    wald_test(coefficients, robust_cov, &slopes)
+}
+
+/// Computes externally studentized residuals t_i = e_i / (s_(i) * sqrt(1 - h_ii)), where the
+/// residual variance s_(i)^2 is estimated with the i-th observation left out.
+pub fn studentized_residuals(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    coefficients: &Array1<f64>,
+) -> Array1<f64> {
+    let residuals = y - &x.dot(coefficients);
+    let h = leverage(x);
+    let s2 = leave_one_out_residual_variances(&residuals, &h, x.shape()[1]);
+    Array1::from_iter((0..residuals.len()).map(|i| residuals[i] / (s2[i] * (1. - h[i])).sqrt()))
+}
+
+/// Residual variances s_(i)^2 = (RSS - e_i^2 / (1 - h_ii)) / (n - k - 1) of the fits with the
+/// i-th observation left out, without refitting.
+fn leave_one_out_residual_variances(
+    residuals: &Array1<f64>,
+    h: &Array1<f64>,
+    n_features: usize,
+) -> Array1<f64> {
+    let dof = (residuals.len() - n_features - 1) as f64;
+    let rss = residuals.dot(residuals);
+    Array1::from_iter(
+        residuals
+            .iter()
+            .zip(h.iter())
+            .map(|(e, h_i)| (rss - e * e / (1. - h_i)) / dof),
+    )
+}
+
+/// Computes the COVRATIO influence diagnostic of each observation: the ratio of the determinant
+/// of the coefficient covariance with the observation deleted to that with all observations,
+/// 1 / ((1 - h_ii) * ((n - k - 1 + t_i^2) / (n - k))^k).
+///
+/// Values far from 1 flag influential observations: below 1 the observation inflates the
+/// coefficient covariance (e.g. outliers), above 1 it improves precision (e.g. high leverage).
+pub fn covratio(y: &Array1<f64>, x: &Array2<f64>, coefficients: &Array1<f64>) -> Array1<f64> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let h = leverage(x);
+    let t = studentized_residuals(y, x, coefficients);
+    let dof = (n_samples - n_features) as f64;
+    Array1::from_iter(h.iter().zip(t.iter()).map(|(h_i, t_i)| {
+        1. / ((1. - h_i) * ((dof - 1. + t_i * t_i) / dof).powi(n_features as i32))
+    }))
+}
+
+/// Computes Akaike weights w_i = exp(-0.5 * (AIC_i - AIC_min)) / sum_j exp(-0.5 * (AIC_j - AIC_min))
+/// for a set of candidate models, i.e. the relative likelihood of each model normalized to sum
+/// to one (e.g. for model averaging).
+pub fn akaike_weights(aics: &Array1<f64>) -> Array1<f64> {
+    assert!(!aics.is_empty(), "at least one model is required");
+    // shifting by the smallest AIC avoids underflow and leaves the weights unchanged
+    let aic_min = aics.iter().copied().fold(f64::INFINITY, f64::min);
+    let relative_likelihood = aics.mapv(|aic| (-0.5 * (aic - aic_min)).exp());
+    let total = relative_likelihood.sum();
+    relative_likelihood / total
+}
+
+/// Computes the coefficient of determination R^2 = 1 - RSS / TSS of fitted values `y_hat`.
+///
+/// With `sample_weights` both the residual and total sums of squares are weighted, and the TSS
+/// is taken around the weighted mean, giving the R^2 consistent with a WLS fit. Setting
+/// `centered` to `false` uses the uncentered TSS (sum of w * y^2), as appropriate for models
+/// without an intercept. If not provided, `centered` defaults to `true`.
+pub fn r_squared(
+    y: &Array1<f64>,
+    y_hat: &Array1<f64>,
+    sample_weights: Option<&Array1<f64>>,
+    centered: Option<bool>,
+) -> f64 {
+    assert_eq!(
+        y.len(),
+        y_hat.len(),
+        "y and y_hat must have the same length"
+    );
+    let weights = match sample_weights {
+        Some(weights) => {
+            assert_eq!(
+                weights.len(),
+                y.len(),
+                "one weight per observation is required"
+            );
+            weights.to_owned()
+        }
+        None => Array1::<f64>::ones(y.len()),
+    };
+    let residuals = y - y_hat;
+    let rss = (&weights * &residuals * &residuals).sum();
+    let y_mean = if centered.unwrap_or(true) {
+        (&weights * y).sum() / weights.sum()
+    } else {
+        0.
+    };
+    let deviations = y - y_mean;
+    let tss = (&weights * &deviations * &deviations).sum();
+    1. - rss / tss
+}
+
+/// Computes the out-of-sample (predictive) R^2 of an OLS model fitted on a training set and
+/// evaluated on a held-out test set: 1 - RSS_test / TSS_test, where TSS_test is taken around
+/// the training mean of y. A model only scores above zero if it predicts the test targets better
+/// than the training mean would, so unlike the in-sample R^2 it can be negative.
+pub fn oos_r_squared(
+    y_train: &Array1<f64>,
+    x_train: &Array2<f64>,
+    y_test: &Array1<f64>,
+    x_test: &Array2<f64>,
+    solve_method: Option<SolveMethod>,
+) -> f64 {
+    assert_eq!(
+        x_train.shape()[1],
+        x_test.shape()[1],
+        "train and test features must have the same number of columns"
+    );
+    let coefficients = solve_ols(y_train, x_train, solve_method, None);
+    let residuals = y_test - &x_test.dot(&coefficients);
+    let deviations = y_test - y_train.mean().unwrap();
+    1. - residuals.dot(&residuals) / deviations.dot(&deviations)
+}
+
+/// Coefficient estimates and their inference statistics as parallel vectors (one entry per
+/// feature, in feature order), ready to be turned into the fields of a Polars struct series.
+#[derive(Clone, Debug)]
+pub struct CoefficientTable {
+    pub names: Vec<String>,
+    pub coefficients: Vec<f64>,
+    pub standard_errors: Vec<f64>,
+    pub t_values: Vec<f64>,
+    pub p_values: Vec<f64>,
+}
+
+/// Packages feature names, coefficients, standard errors, t-values and p-values into a
+/// [`CoefficientTable`], checking that all of them describe the same features.
+pub fn as_coefficient_table(
+    names: &[&str],
+    coefficients: &Array1<f64>,
+    standard_errors: &Array1<f64>,
+    t_values: &Array1<f64>,
+    p_values: &Array1<f64>,
+) -> CoefficientTable {
+    let n_features = names.len();
+    assert!(
+        coefficients.len() == n_features
+            && standard_errors.len() == n_features
+            && t_values.len() == n_features
+            && p_values.len() == n_features,
+        "names, coefficients, standard errors, t-values & p-values must have the same length"
+    );
+    CoefficientTable {
+        names: names.iter().map(|name| name.to_string()).collect(),
+        coefficients: coefficients.to_vec(),
+        standard_errors: standard_errors.to_vec(),
+        t_values: t_values.to_vec(),
+        p_values: p_values.to_vec(),
+    }
+}
+
+fn sigmoid(z: f64) -> f64 {
+    1. / (1. + (-z).exp())
+}
+
+/// Detects (quasi-)complete separation of binary targets `y` (0 or 1) by the features, in which
+/// case the logistic regression MLE does not exist and IRLS diverges to infinite coefficients.
+///
+/// Runs Newton iterations on the logistic deviance, halving each step until the deviance does
+/// not increase. For overlapping classes the deviance is strictly convex with a finite minimum,
+/// so the iterations converge, however large the linear predictor at the MLE (e.g. for unscaled
+/// features). Under separation the deviance keeps decreasing as the coefficients grow along a
+/// separating direction: the data is reported as separated when the iterations do not converge
+/// while ||w|| keeps growing and some fitted probabilities saturate (|x_i^T w| > 30, i.e. within
+/// 1e-13 of 0 or 1). Callers can then warn or switch to a penalized fit, e.g.
+/// [`solve_logistic_firth`].
+pub fn detect_separation(y: &Array1<f64>, x: &Array2<f64>) -> bool {
+    const MAX_ITER: usize = 100;
+    const MAX_HALVINGS: usize = 30;
+    const MAX_ETA: f64 = 30.;
+    assert!(
+        y.iter().all(|y_i| *y_i == 0. || *y_i == 1.),
+        "targets must be binary (0 or 1)"
+    );
+    // deviance 2 sum_i log(1 + exp(eta_i)) - y_i eta_i, with a stable softplus
+    let deviance = |w: &Array1<f64>| -> f64 {
+        let eta = x.dot(w);
+        2. * eta
+            .iter()
+            .zip(y.iter())
+            .map(|(eta_i, y_i)| eta_i.max(0.) + (-eta_i.abs()).exp().ln_1p() - y_i * eta_i)
+            .sum::<f64>()
+    };
+
+    let n_features = x.shape()[1];
+    let mut w = Array1::<f64>::zeros(n_features);
+    let mut current_deviance = deviance(&w);
+    let mut norms = vec![0.];
+    for _ in 0..MAX_ITER {
+        let p = x.dot(&w).mapv(sigmoid);
+        let weights = &p * &(1. - &p);
+        let xtwx = x.t().dot(&(x * &weights.view().insert_axis(Axis(1))));
+        let score = x.t().dot(&(y - &p));
+        let step = solve_normal_equations(&xtwx, &score, true);
+        if !step.iter().all(|s| s.is_finite()) {
+            break; // the information matrix became singular as probabilities saturated
+        }
+        if step.dot(&step).sqrt() < 1e-10 * (1. + w.dot(&w).sqrt()) {
+            return false; // converged to a finite MLE
+        }
+        let mut step_size = 1.;
+        let mut accepted = false;
+        for _ in 0..MAX_HALVINGS {
+            let w_new = &w + &(&step * step_size);
+            let new_deviance = deviance(&w_new);
+            if new_deviance <= current_deviance {
+                (w, current_deviance, accepted) = (w_new, new_deviance, true);
+                break;
+            }
+            step_size *= 0.5;
+        }
+        if !accepted {
+            break; // the deviance can not decrease any further in floating point
+        }
+        norms.push(w.dot(&w).sqrt());
+    }
+    // no convergence: separated if the coefficients diverge, i.e. ||w|| grew substantially over
+    // the second half of the iterations, pushing fitted probabilities to 0 or 1
+    let norm = norms[norms.len() - 1];
+    let diverging = norm > 1.5 * norms[norms.len() / 2];
+    diverging && x.dot(&w).iter().any(|eta_i| eta_i.abs() > MAX_ETA)
+}
+
+/// Solves Firth-penalized logistic regression for binary targets `y` (0 or 1).
+///
+/// Maximizes the log-likelihood plus the Jeffreys-prior penalty 0.5 * log|X^T W X|, which
+/// removes the first-order bias of the MLE and keeps the estimates finite under separation.
+/// Each Newton step uses the modified score sum_i (y_i - p_i + h_i (0.5 - p_i)) x_i, where h_i
+/// is the diagonal of W^(1/2) X inv(X^T W X) X^T W^(1/2), and is capped at a maximum absolute
+/// change of 5 per coefficient for stability.
+///
+/// # Arguments
+///
+/// * `max_iter` - Optional maximum number of Newton iterations. Defaults to 100.
+/// * `tol` - Optional tolerance on the largest absolute coefficient change. Defaults to 1e-8.
+pub fn solve_logistic_firth(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+) -> Array1<f64> {
+    const MAX_STEP: f64 = 5.;
+    let max_iter = max_iter.unwrap_or(100);
+    let tol = tol.unwrap_or(1e-8);
+    assert!(
+        y.iter().all(|y_i| *y_i == 0. || *y_i == 1.),
+        "targets must be binary (0 or 1)"
+    );
+    let n_features = x.shape()[1];
+    let mut w = Array1::<f64>::zeros(n_features);
+    for _ in 0..max_iter {
+        let p = x.dot(&w).mapv(sigmoid);
+        let weights = &p * &(1. - &p);
+        let xtwx_inv = inv(
+            &x.t().dot(&(x * &weights.view().insert_axis(Axis(1)))),
+            true,
+            None,
+        );
+        let h = &(&x.dot(&xtwx_inv) * x).sum_axis(Axis(1)) * &weights;
+        let score = x.t().dot(&(y - &p + &h * &(0.5 - &p)));
+        let mut step = xtwx_inv.dot(&score);
+        let largest = step.iter().fold(0., |acc: f64, s| acc.max(s.abs()));
+        if largest > MAX_STEP {
+            step *= MAX_STEP / largest;
+        }
+        w += &step;
+        if largest < tol {
+            break;
+        }
+    }
+    w
+}
+
+/// Solves a quasi-binomial GLM for proportions `y` in [0, 1]: logit link and variance function
+/// phi * mu (1 - mu), with a dispersion phi estimated from the data (rather than fixed at 1 as
+/// for the binomial), which accommodates over- or underdispersed rates.
+///
+/// The coefficients solve the same quasi-score equations as a logistic regression and are
+/// found by IRLS (Newton) iterations; phi does not affect them. It is estimated by the Pearson
+/// statistic sum_i (y_i - mu_i)^2 / (mu_i (1 - mu_i)) / (n - k), which scales the coefficient
+/// covariance inv(X^T W X).
+///
+/// # Arguments
+///
+/// * `max_iter` - Optional maximum number of IRLS iterations. Defaults to 100.
+/// * `tol` - Optional tolerance on the largest absolute coefficient change. Defaults to 1e-8.
+///
+/// # Returns
+///
+/// A tuple of the coefficients and the estimated dispersion phi.
+pub fn solve_quasibinomial(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+) -> (Array1<f64>, f64) {
+    let max_iter = max_iter.unwrap_or(100);
+    let tol = tol.unwrap_or(1e-8);
+    assert!(
+        y.iter().all(|y_i| (0. ..=1.).contains(y_i)),
+        "targets must be proportions in [0, 1]"
+    );
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    assert!(
+        n_samples > n_features,
+        "more observations than features are required to estimate the dispersion"
+    );
+    let mut w = Array1::<f64>::zeros(n_features);
+    for _ in 0..max_iter {
+        let mu = x.dot(&w).mapv(sigmoid);
+        let weights = &mu * &(1. - &mu);
+        let xtwx = x.t().dot(&(x * &weights.view().insert_axis(Axis(1))));
+        let step = solve_normal_equations(&xtwx, &x.t().dot(&(y - &mu)), true);
+        w += &step;
+        if step.iter().fold(0., |acc: f64, s| acc.max(s.abs())) < tol {
+            break;
+        }
+    }
+    let mu = x.dot(&w).mapv(sigmoid);
+    let pearson: f64 = y
+        .iter()
+        .zip(mu.iter())
+        .map(|(y_i, mu_i)| (y_i - mu_i).powi(2) / (mu_i * (1. - mu_i)))
+        .sum();
+    (w, pearson / (n_samples - n_features) as f64)
+}
+
+/// Distribution family of a generalized linear model, each with its canonical link.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum GlmFamily {
+    /// Binary (or proportion) targets with the logit link.
+    Binomial,
+    /// Count targets with the log link.
+    Poisson,
+}
+
+impl GlmFamily {
+    fn inverse_link(&self, eta: f64) -> f64 {
+        match self {
+            GlmFamily::Binomial => sigmoid(eta),
+            GlmFamily::Poisson => eta.exp(),
+        }
+    }
+
+    /// Variance function, which is also the IRLS weight under the canonical link.
+    fn variance(&self, mu: f64) -> f64 {
+        match self {
+            GlmFamily::Binomial => mu * (1. - mu),
+            GlmFamily::Poisson => mu,
+        }
+    }
+
+    fn deviance(&self, y: &Array1<f64>, mu: &Array1<f64>) -> f64 {
+        // y ln(y / mu) with the convention 0 ln 0 = 0
+        let xlogy = |a: f64, b: f64| if a == 0. { 0. } else { a * (a / b).ln() };
+        let total: f64 = y
+            .iter()
+            .zip(mu.iter())
+            .map(|(y_i, mu_i)| match self {
+                GlmFamily::Binomial => xlogy(*y_i, *mu_i) + xlogy(1. - y_i, 1. - mu_i),
+                GlmFamily::Poisson => xlogy(*y_i, *mu_i) - (y_i - mu_i),
+            })
+            .sum();
+        2. * total
+    }
+}
+
+/// Fits a canonical-link GLM with a fixed `offset` added to the linear predictor by IRLS
+/// (Newton) iterations started at `initial`, returning the coefficients and the deviance.
+fn fit_glm_offset(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    offset: &Array1<f64>,
+    family: GlmFamily,
+    initial: Array1<f64>,
+) -> (Array1<f64>, f64) {
+    const MAX_ITER: usize = 100;
+    const TOL: f64 = 1e-10;
+    let mut w = initial;
+    if x.shape()[1] > 0 {
+        for _ in 0..MAX_ITER {
+            let mu = (&x.dot(&w) + offset).mapv(|eta| family.inverse_link(eta));
+            let weights = mu.mapv(|mu_i| family.variance(mu_i));
+            let xtwx = x.t().dot(&(x * &weights.view().insert_axis(Axis(1))));
+            let step = solve_normal_equations(&xtwx, &x.t().dot(&(y - &mu)), true);
+            w += &step;
+            if step.iter().fold(0., |acc: f64, s| acc.max(s.abs())) < TOL {
+                break;
+            }
+        }
+    }
+    let mu = (&x.dot(&w) + offset).mapv(|eta| family.inverse_link(eta));
+    let deviance = family.deviance(y, &mu);
+    (w, deviance)
+}
+
+/// Quantile function of the chi-squared distribution with `df` degrees of freedom (by bisection).
+fn chi2_ppf(p: f64, df: f64) -> f64 {
+    assert!(
+        p > 0. && p < 1.,
+        "probability must be strictly between 0 and 1"
+    );
+    let (mut lo, mut hi) = (0., df.max(1.));
+    while 1. - chi2_sf(hi, df) < p {
+        hi *= 2.;
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if 1. - chi2_sf(mid, df) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+        if hi - lo < 1e-12 * (1. + mid) {
+            break;
+        }
     }
+    0.5 * (lo + hi)
+}
 
-    // Use woodbury to propagate inv(X.T X) & (X.T Y)
-    if use_woodbury {
-        // assign warm-up coefficients
-        let mut xtx_inv = inv(&xtx, false);
-        let coef_warmup = xtx_inv.t().dot(&xty);
-        coefficients
-            .slice_mut(s![min_periods - 1, ..])
-            .assign(&coef_warmup);
+/// Computes profile-likelihood confidence intervals for the coefficients of a canonical-link
+/// GLM fitted at (the MLE) `coefficients`, which remain accurate where Wald intervals based on
+/// the curvature at the estimate do not, e.g. for small samples or near separation.
+///
+/// For each coefficient j the profile deviance D(c) is the deviance of the model refitted with
+/// b_j fixed at c (entering as the offset c x_j). The interval bounds are the values of c on
+/// either side of the estimate where D(c) - D(b) equals the `level` quantile of the chi-squared
+/// distribution with one degree of freedom, located by bracketing outward in steps of the Wald
+/// standard error and bisection. A bound is infinite if the profile deviance never reaches the
+/// threshold, as happens for coefficients that diverge under (quasi-)separation.
+///
+/// # Arguments
+///
+/// * `level` - Optional confidence level. Defaults to 0.95.
+///
+/// # Returns
+///
+/// A tuple of the lower and upper bounds.
+pub fn glm_profile_ci(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    coefficients: &Array1<f64>,
+    level: Option<f64>,
+    family: GlmFamily,
+) -> (Array1<f64>, Array1<f64>) {
+    const MAX_DOUBLINGS: usize = 30;
+    let level = level.unwrap_or(0.95);
+    match family {
+        GlmFamily::Binomial => assert!(
+            y.iter().all(|y_i| (0. ..=1.).contains(y_i)),
+            "binomial targets must be in [0, 1]"
+        ),
+        GlmFamily::Poisson => assert!(
+            y.iter().all(|y_i| *y_i >= 0.),
+            "poisson targets must be non-negative"
+        ),
+    }
+    let n_features = x.shape()[1];
+    let threshold = chi2_ppf(level, 1.);
+    let mu = x.dot(coefficients).mapv(|eta| family.inverse_link(eta));
+    let deviance_hat = family.deviance(y, &mu);
+    let weights = mu.mapv(|mu_i| family.variance(mu_i));
+    let covariance = inv(
+        &x.t().dot(&(x * &weights.view().insert_axis(Axis(1)))),
+        true,
+        None,
+    );
 
-        // make c [[-1, 0], [0, 1]]; which drops old and adds new
-        let c: Array2<f64> = array![[-1., 0.], [0., 1.]];
+    let mut lower = Array1::<f64>::zeros(n_features);
+    let mut upper = Array1::<f64>::zeros(n_features);
+    for j in 0..n_features {
+        let others: Vec<usize> = (0..n_features).filter(|i| *i != j).collect();
+        let x_others = x.select(Axis(1), &others);
+        let initial = coefficients.select(Axis(0), &others);
+        let x_j = x.column(j).to_owned();
+        // excess profile deviance over the threshold, positive outside the interval
+        let excess = |c: f64| {
+            let (_, deviance) = fit_glm_offset(y, &x_others, &(&x_j * c), family, initial.clone());
+            deviance - deviance_hat - threshold
+        };
+        let b_j = coefficients[j];
+        let step = {
+            let se = covariance[[j, j]].sqrt();
+            if se.is_finite() && se > 0. {
+                se
+            } else {
+                1.
+            }
+        };
+        for (direction, bound) in [(-1., &mut lower[j]), (1., &mut upper[j])] {
+            let (mut inside, mut outside) = (0., step);
+            let mut bracketed = false;
+            for _ in 0..MAX_DOUBLINGS {
+                if excess(b_j + direction * outside) > 0. {
+                    bracketed = true;
+                    break;
+                }
+                inside = outside;
+                outside *= 2.;
+            }
+            *bound = if bracketed {
+                for _ in 0..100 {
+                    let mid = 0.5 * (inside + outside);
+                    if excess(b_j + direction * mid) > 0. {
+                        outside = mid;
+                    } else {
+                        inside = mid;
+                    }
+                    if outside - inside < 1e-10 * (1. + mid) {
+                        break;
+                    }
+                }
+                b_j + direction * 0.5 * (inside + outside)
+            } else {
+                direction * f64::INFINITY
+            };
+        }
+    }
+    (lower, upper)
+}
 
-        // Slide the window and update coefficients
-        for i in min_periods..n {
-            let i_start = i.saturating_sub(window_size);
-            let x_new = x.row(i);
+/// Splits `n_samples` temporally ordered observations into `n_blocks` contiguous validation
+/// blocks, returning (train indices, validation indices) for each fold.
+///
+/// The `purge` observations immediately before and after each validation block are dropped
+/// from its training set, so that serially correlated neighbours can not leak information
+/// across the block boundaries.
+pub fn blocked_cv_folds(
+    n_samples: usize,
+    n_blocks: usize,
+    purge: usize,
+) -> Vec<(Vec<usize>, Vec<usize>)> {
+    assert!(
+        n_blocks >= 2 && n_blocks <= n_samples,
+        "n_blocks must be between 2 and the number of samples"
+    );
+    (0..n_blocks)
+        .map(|b| {
+            // evenly spaced block boundaries, so block sizes differ by at most one
+            let start = b * n_samples / n_blocks;
+            let end = (b + 1) * n_samples / n_blocks;
+            let train = (0..n_samples)
+                .filter(|i| *i + purge < start || *i >= end + purge)
+                .collect();
+            (train, (start..end).collect())
+        })
+        .collect()
+}
 
-            if i > window_size - 1 {
-                let x_prev = x.row(i_start);
+/// Computes the blocked (time series) cross-validation score of a ridge model: the mean squared
+/// error on each contiguous validation block (see [`blocked_cv_folds`]) of a fit on the
+/// remaining, purged, observations, averaged over blocks. `alpha = 0` gives OLS.
+pub fn blocked_cv_score(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    n_blocks: usize,
+    alpha: f64,
+    purge: Option<usize>,
+) -> f64 {
+    let folds = blocked_cv_folds(y.len(), n_blocks, purge.unwrap_or(0));
+    let mut total = 0.;
+    for (train, validation) in folds.iter() {
+        assert!(
+            !train.is_empty(),
+            "purge gap leaves no training observations"
+        );
+        let coefficients = solve_ridge(
+            &y.select(Axis(0), train),
+            &x.select(Axis(0), train),
+            alpha,
+            None,
+            None,
+        );
+        let errors =
+            y.select(Axis(0), validation) - x.select(Axis(0), validation).dot(&coefficients);
+        total += errors.dot(&errors) / validation.len() as f64;
+    }
+    total / folds.len() as f64
+}
 
-                // create rank 2 update array
-                let mut x_update = ndarray::stack(Axis(0), &[x_prev, x_new]).unwrap(); // 2 x K
+/// Computes the sample autocorrelation function of the residuals at lags 1..=n_lags:
+/// r_k = sum_{t > k} (e_t - mean(e)) (e_{t-k} - mean(e)) / sum_t (e_t - mean(e))^2.
+pub fn residual_acf(residuals: &Array1<f64>, n_lags: usize) -> Array1<f64> {
+    let n = residuals.len();
+    assert!(
+        n_lags < n,
+        "n_lags must be smaller than the number of residuals"
+    );
+    let centered = residuals - residuals.mean().unwrap();
+    let denominator = centered.dot(&centered);
+    Array1::from_iter(
+        (1..=n_lags)
+            .map(|k| centered.slice(s![k..]).dot(&centered.slice(s![..n - k])) / denominator),
+    )
+}
 
-                // multiply x_old row by -1.0 (subtract the previous contribution)
-                x_update.row_mut(0).mapv_inplace(|elem| -elem);
+/// Solves a least squares problem with per-observation weights: min sum_i w_i (y_i - x_i B)^2.
+fn solve_weighted_least_squares(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    weights: &Array1<f64>,
+) -> Array1<f64> {
+    let sqrt_w = weights.mapv(f64::sqrt);
+    solve_ols(
+        &(y * &sqrt_w),
+        &(x * &sqrt_w.view().insert_axis(Axis(1))),
+        None,
+        None,
+    )
+}
 
-                // update inv(XTX) and XTY
-                xtx_inv = update_xtx_inv(&xtx_inv, &x_update, Some(&c));
-                xty = xty + &x_new * y[i]  // add new contribution
-                    - &x_prev * y[i_start] // subtract old contribution
-                ;
+/// Solves (weighted) linear quantile regression: min sum_i w_i * rho_q(y_i - x_i B), where
+/// rho_q(r) = r * (q - 1{r < 0}) is the pinball loss of the `quantile` q.
+///
+/// Uses iteratively reweighted least squares, starting from the (weighted) OLS fit, with IRLS
+/// weights w_i * |q - 1{r_i < 0}| / max(|r_i|, eps). Iterations stop once the relative change
+/// of the objective falls below `tol`.
+///
+/// # Arguments
+///
+/// * `weights` - Optional non-negative observation weights scaling each pinball loss term
+///   (e.g. importance weights). Defaults to uniform weights.
+/// * `max_iter` - Optional maximum number of IRLS iterations. Defaults to 1000.
+/// * `tol` - Optional tolerance on the relative change of the objective. Defaults to 1e-8.
+pub fn solve_quantile(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    quantile: f64,
+    weights: Option<&Array1<f64>>,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+) -> Array1<f64> {
+    const EPS: f64 = 1e-8;
+    assert!(
+        quantile > 0. && quantile < 1.,
+        "quantile must be strictly between 0 and 1"
+    );
+    let max_iter = max_iter.unwrap_or(1_000);
+    let tol = tol.unwrap_or(1e-8);
+    let weights = match weights {
+        Some(weights) => {
+            assert_eq!(
+                weights.len(),
+                y.len(),
+                "one weight per observation is required"
+            );
+            assert!(
+                weights.iter().all(|w| *w >= 0.),
+                "weights must be non-negative"
+            );
+            weights.to_owned()
+        }
+        None => Array1::<f64>::ones(y.len()),
+    };
+    let pinball = |r: f64| {
+        if r < 0. {
+            (quantile - 1.) * r
+        } else {
+            quantile * r
+        }
+    };
+    let objective = |coefficients: &Array1<f64>| {
+        (y - &x.dot(coefficients))
+            .iter()
+            .zip(weights.iter())
+            .map(|(r, w)| w * pinball(*r))
+            .sum::<f64>()
+    };
+
+    let mut coefficients = solve_weighted_least_squares(y, x, &weights);
+    let mut objective_old = objective(&coefficients);
+    for _ in 0..max_iter {
+        let residuals = y - &x.dot(&coefficients);
+        let irls_weights = Array1::from_iter(residuals.iter().zip(weights.iter()).map(|(r, w)| {
+            let slope = if *r < 0. { 1. - quantile } else { quantile };
+            w * slope / r.abs().max(EPS)
+        }));
+        coefficients = solve_weighted_least_squares(y, x, &irls_weights);
+        let objective_new = objective(&coefficients);
+        if (objective_old - objective_new).abs() <= tol * objective_old.max(f64::MIN_POSITIVE) {
+            break;
+        }
+        objective_old = objective_new;
+    }
+    coefficients
+}
+
+/// Median of the (non-empty) values.
+fn median(values: &Array1<f64>) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let n = sorted.len();
+    if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        0.5 * (sorted[n / 2 - 1] + sorted[n / 2])
+    }
+}
+
+/// Robust scale estimate of residuals: the median absolute deviation from the median, divided
+/// by 0.6745 so that it is consistent for the standard deviation of normal errors.
+fn mad_scale(residuals: &Array1<f64>) -> f64 {
+    let center = median(residuals);
+    median(&residuals.mapv(|r| (r - center).abs())) / 0.6745
+}
+
+/// Standardizes residuals robustly as (e_i - median(e)) / MAD, with the MAD normalized to be
+/// consistent for the standard deviation of normal errors (see [`solve_huber`]). Values beyond
+/// +/- 3 are robust outlier candidates. Unlike [`studentized_residuals`] this ignores leverage,
+/// but neither the center nor the scale is inflated by the outliers themselves.
+pub fn mad_standardized_residuals(residuals: &Array1<f64>) -> Array1<f64> {
+    let center = median(residuals);
+    let scale = mad_scale(residuals);
+    residuals.mapv(|r| (r - center) / scale)
+}
+
+/// Solves a robust Huber regression by iteratively reweighted least squares (IRLS).
+///
+/// Each iteration standardizes the residuals by a robust scale estimate (the normalized MAD)
+/// and assigns Huber weights w_i = 1 if |r_i| <= delta and delta / |r_i| otherwise, so that
+/// outliers only have a linear rather than quadratic influence. The weighted fit solves the
+/// normal equations X^T W X B = X^T W y. Starts from the OLS fit and stops once the Huber
+/// objective sum_i rho(r_i / scale), evaluated at the scale of the current iteration, changes
+/// by less than `tol` relative to its previous value. Stable weights alone are no stopping
+/// criterion, as they may settle early while the re-estimated MAD keeps drifting.
+///
+/// # Arguments
+///
+/// * `delta` - Threshold on the standardized residuals, e.g. 1.345 for 95% efficiency under
+///   normal errors.
+/// * `max_iter` - Optional maximum number of IRLS iterations. Defaults to 100.
+/// * `tol` - Optional tolerance on the relative change of the objective. Defaults to 1e-8.
+/// * `scale` - Optional known noise scale, see [`solve_huber_with_weights`].
+pub fn solve_huber(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    delta: f64,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    scale: Option<f64>,
+) -> Array1<f64> {
+    solve_huber_with_weights(y, x, delta, max_iter, tol, scale).0
+}
+
+/// Solves a robust Huber regression as [`solve_huber`], additionally returning the final IRLS
+/// weights of the observations, in (0, 1]. Weights well below one flag the observations which
+/// were downweighted as outliers.
+///
+/// A known noise `scale` is used as is instead of re-estimating the MAD of the residuals in
+/// every iteration, which stabilizes fits on small samples where the MAD is noisy. The weights
+/// then only depend on the coefficients, so the iterations also stop once no weight changes by
+/// more than `tol`.
+pub fn solve_huber_with_weights(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    delta: f64,
+    max_iter: Option<usize>,
+    tol: Option<f64>,
+    scale: Option<f64>,
+) -> (Array1<f64>, Array1<f64>) {
+    if let Some(scale) = scale {
+        assert!(scale > 0., "scale must be strictly positive");
+    }
+    assert!(delta > 0., "delta must be strictly positive");
+    let max_iter = max_iter.unwrap_or(100);
+    let tol = tol.unwrap_or(1e-8);
+
+    let mut coefficients = solve_ols(y, x, None, None);
+    let mut weights = Array1::<f64>::ones(y.len());
+    let fixed_scale = scale.is_some();
+    for _ in 0..max_iter {
+        let residuals = y - &x.dot(&coefficients);
+        let scale = scale.unwrap_or_else(|| mad_scale(&residuals));
+        if scale <= f64::EPSILON {
+            break; // (near) exact fit of the majority of observations
+        }
+        let weights_new = residuals.mapv(|r| {
+            let u = (r / scale).abs();
+            if u <= delta {
+                1.
             } else {
-                let x_update = x_new.insert_axis(Axis(0)).into_owned(); // 1 x K
-                xtx_inv = update_xtx_inv(&xtx_inv, &x_update, None);
-                xty = xty + &x_new * y[i];
+                delta / u
             }
-            coefficients.slice_mut(s![i, ..]).assign(&xtx_inv.dot(&xty));
+        });
+        let x_weighted = x * &weights_new.view().insert_axis(Axis(1));
+        coefficients = solve_normal_equations(&x_weighted.t().dot(x), &x_weighted.t().dot(y), true);
+        let change = (&weights_new - &weights)
+            .mapv(f64::abs)
+            .fold(0., |a: f64, b| a.max(*b));
+        weights = weights_new;
+        // the objective is compared at a fixed scale, as the MAD itself moves between iterations
+        let objective_old = huber_objective(&residuals, scale, delta);
+        let objective_new = huber_objective(&(y - &x.dot(&coefficients)), scale, delta);
+        let objective_change =
+            (objective_old - objective_new).abs() / objective_old.max(f64::EPSILON);
+        // with a fixed scale the weights only depend on the coefficients, so stable weights
+        // mark a fixed point; with a re-estimated MAD they may settle while the fit still moves
+        if objective_change <= tol || (fixed_scale && change <= tol) {
+            break;
         }
-    } else {
-        // update X.T X & X.T Y and solve normal equations at every time step
-        // assign warm-up coefficients
-        let coef_warmup = solve_normal_equations(&xtx, &xty, false);
+    }
+    (coefficients, weights)
+}
 
-        coefficients
-            .slice_mut(s![min_periods - 1, ..])
-            .assign(&coef_warmup);
+/// Huber objective sum_i rho(r_i / scale) with rho(u) = u^2 / 2 for |u| <= delta and
+/// delta |u| - delta^2 / 2 otherwise.
+fn huber_objective(residuals: &Array1<f64>, scale: f64, delta: f64) -> f64 {
+    residuals
+        .iter()
+        .map(|r| {
+            let u = (r / scale).abs();
+            if u <= delta {
+                0.5 * u * u
+            } else {
+                delta * u - 0.5 * delta * delta
+            }
+        })
+        .sum()
+}
 
-        // Slide the window and update coefficients
-        for i in min_periods..n {
-            let i_start = i.saturating_sub(window_size);
-            // update XTX w/ latest data point
-            let x_new = x.row(i);
+/// Psi (influence) function of an M-estimator, applied to residuals standardized by the scale.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum PsiFunction {
+    /// Huber's psi(u) = clamp(u, -c, c) with threshold c (e.g. 1.345, see [`solve_huber`]).
+    Huber(f64),
+    /// Tukey's bisquare psi(u) = u (1 - (u / c)^2)^2 for |u| <= c and 0 beyond (e.g. c = 4.685).
+    Tukey(f64),
+}
 
-            // Add new contributions
-            xtx += &outer_product(&x_new, &x_new);
-            xty = xty + &x_new * y[i];
+impl PsiFunction {
+    fn psi(&self, u: f64) -> f64 {
+        match self {
+            PsiFunction::Huber(c) => u.clamp(-c, *c),
+            PsiFunction::Tukey(c) if u.abs() <= *c => u * (1. - (u / c).powi(2)).powi(2),
+            PsiFunction::Tukey(_) => 0.,
+        }
+    }
 
-            // Subtract the previous contribution
-            if i > window_size - 1 {
-                let x_prev = x.row(i_start);
-                xtx -= &outer_product(&x_prev, &x_prev);
-                xty = xty - &x_prev * y[i_start];
+    fn psi_derivative(&self, u: f64) -> f64 {
+        match self {
+            PsiFunction::Huber(c) if u.abs() <= *c => 1.,
+            PsiFunction::Tukey(c) if u.abs() <= *c => {
+                let v = (u / c).powi(2);
+                (1. - v) * (1. - 5. * v)
             }
+            _ => 0.,
+        }
+    }
+}
 
-            // update coefficients
-            let coefficients_i = solve_normal_equations(&xtx, &xty, true);
-            coefficients.slice_mut(s![i, ..]).assign(&coefficients_i);
+/// Computes the standard errors of an M-estimator (e.g. [`solve_huber`]) from the asymptotic
+/// sandwich covariance s^2 inv(M) Q inv(M), with M = sum_i psi'(u_i) x_i x_i^T and
+/// Q = sum_i psi(u_i)^2 x_i x_i^T for the standardized residuals u_i = (y_i - x_i^T B) / s.
+///
+/// The classical OLS standard errors ignore the downweighting of large residuals and are
+/// wrong for robust fits. Under normal errors with Huber's c = 1.345 this gives the well-known
+/// 1 / 0.95 inflation of the OLS variance. Without a `scale` the normalized MAD of the
+/// residuals is used, as in [`solve_huber`].
+pub fn robust_estimator_se(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    coefficients: &Array1<f64>,
+    psi_function: PsiFunction,
+    scale: Option<f64>,
+) -> Array1<f64> {
+    let residuals = y - &x.dot(coefficients);
+    let scale = scale.unwrap_or_else(|| mad_scale(&residuals));
+    assert!(scale > 0., "scale must be strictly positive");
+    let u = residuals / scale;
+    let bread_weights = u.mapv(|u| psi_function.psi_derivative(u));
+    let meat_weights = u.mapv(|u| psi_function.psi(u).powi(2));
+    let m = (x * &bread_weights.view().insert_axis(Axis(1))).t().dot(x);
+    let q = (x * &meat_weights.view().insert_axis(Axis(1))).t().dot(x);
+    // M is not positive definite for redescending psi functions in general
+    let m_inv = inv(&m, false, None);
+    (m_inv.dot(&q).dot(&m_inv) * (scale * scale))
+        .diag()
+        .mapv(f64::sqrt)
+}
+
+/// Solves a trimmed OLS problem: fits OLS, drops the `trim_fraction` of observations with the
+/// largest absolute residuals and refits OLS on the remaining ones. A simple, transparent
+/// least-trimmed-squares style estimator that removes the influence of gross outliers.
+pub fn solve_trimmed_ols(y: &Array1<f64>, x: &Array2<f64>, trim_fraction: f64) -> Array1<f64> {
+    assert!(
+        (0. ..1.).contains(&trim_fraction),
+        "trim_fraction must be in [0, 1)"
+    );
+    let n_samples = y.len();
+    let n_keep = n_samples - (trim_fraction * n_samples as f64).floor() as usize;
+    assert!(
+        n_keep >= x.shape()[1],
+        "trimming leaves fewer observations than features"
+    );
+    let coefficients = solve_ols(y, x, None, None);
+    if n_keep == n_samples {
+        return coefficients;
+    }
+    let residuals = y - &x.dot(&coefficients);
+    let mut order: Vec<usize> = (0..n_samples).collect();
+    order.sort_by(|a, b| residuals[*a].abs().total_cmp(&residuals[*b].abs()));
+    let mut kept = order[..n_keep].to_vec();
+    kept.sort_unstable(); // preserve the original row order
+    solve_ols(
+        &y.select(Axis(0), &kept),
+        &x.select(Axis(0), &kept),
+        None,
+        None,
+    )
+}
+
+/// Kind of fitted model, determining how its degrees of freedom are counted.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum FitType {
+    OLS,
+    GLM,
+    Ridge,
+    Lasso { n_active: usize }, // number of non-zero coefficients
+}
+
+/// Computes the residual degrees of freedom n - df of a fit, where the model degrees of freedom
+/// df are k for OLS & GLMs, the effective degrees of freedom trace(H) for ridge (which requires
+/// `x` & `alpha`, see [`ridge_hat_trace`]) and the active set size for the lasso.
+pub fn degrees_of_freedom(
+    fit_type: FitType,
+    n: usize,
+    k: usize,
+    x: Option<&Array2<f64>>,
+    alpha: Option<f64>,
+) -> f64 {
+    let model_df = match fit_type {
+        FitType::OLS | FitType::GLM => k as f64,
+        FitType::Ridge => {
+            let x = x.expect("the feature matrix is required for the ridge degrees of freedom");
+            assert_eq!(x.shape()[1], k, "x must have k columns");
+            ridge_hat_trace(x, alpha.unwrap_or(0.))
+        }
+        FitType::Lasso { n_active } => {
+            assert!(
+                n_active <= k,
+                "the active set can not exceed the number of features"
+            );
+            n_active as f64
+        }
+    };
+    n as f64 - model_df
+}
+
+/// Computes jackknife (leave-one-out) standard errors of the OLS coefficients.
+///
+/// Uses the closed form leave-one-out coefficients b_(i) = b - inv(X^T X) x_i e_i / (1 - h_ii),
+/// so no refitting is required, and the jackknife variance (n - 1) / n * sum_i (b_(i) - mean)^2
+/// of each coefficient. These closely approximate the HC3 robust standard errors.
+pub fn jackknife_se(y: &Array1<f64>, x: &Array2<f64>, coefficients: &Array1<f64>) -> Array1<f64> {
+    let n_samples = x.shape()[0];
+    let residuals = y - &x.dot(coefficients);
+    let h = leverage(x);
+    let xtx_inv = inv(&x.t().dot(x), true, None);
+    // row i holds the change b - b_(i) from deleting observation i
+    let scale = &residuals / &(1. - &h);
+    let deltas = x.dot(&xtx_inv) * scale.view().insert_axis(Axis(1));
+    let mean = deltas.mean_axis(Axis(0)).unwrap();
+    let deviations = &deltas - &mean;
+    (&deviations * &deviations)
+        .sum_axis(Axis(0))
+        .mapv(|v| (v * (n_samples - 1) as f64 / n_samples as f64).sqrt())
+}
+
+/// Computes the DFFITS influence diagnostic t_i * sqrt(h_ii / (1 - h_ii)) of each observation,
+/// i.e. the scaled change of its fitted value when it is left out, combining the externally
+/// studentized residual t_i and the leverage h_ii.
+pub fn dffits(y: &Array1<f64>, x: &Array2<f64>, coefficients: &Array1<f64>) -> Array1<f64> {
+    let h = leverage(x);
+    let t = studentized_residuals(y, x, coefficients);
+    &t * &h.mapv(|h_i| (h_i / (1. - h_i)).sqrt())
+}
+
+/// Flags influential observations, whose absolute DFFITS (see [`dffits`]) of an OLS fit exceeds
+/// `threshold` (default: the conventional 2 * sqrt(k / n)), e.g. to refit on the clean subset.
+pub fn flag_outliers(y: &Array1<f64>, x: &Array2<f64>, threshold: Option<f64>) -> Array1<bool> {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let threshold = threshold.unwrap_or(2. * (n_features as f64 / n_samples as f64).sqrt());
+    let coefficients = solve_ols(y, x, None, None);
+    dffits(y, x, &coefficients).mapv(|d| d.abs() > threshold)
+}
+
+/// Computes the DFBETAS influence diagnostics, an (n x k) array whose entry (i, j) is the change
+/// of the j-th coefficient when the i-th observation is left out, scaled by its leave-one-out
+/// standard error: (b_j - b_(i)j) / (s_(i) * sqrt(inv(X^T X)_jj)).
+///
+/// Uses the closed form b - b_(i) = inv(X^T X) x_i e_i / (1 - h_ii). Absolute values above the
+/// conventional 2 / sqrt(n) flag observations driving a particular coefficient.
+pub fn dfbetas(y: &Array1<f64>, x: &Array2<f64>, coefficients: &Array1<f64>) -> Array2<f64> {
+    let residuals = y - &x.dot(coefficients);
+    let h = leverage(x);
+    let s2 = leave_one_out_residual_variances(&residuals, &h, x.shape()[1]);
+    let xtx_inv = inv(&x.t().dot(x), true, None);
+    let scale = Array1::from_iter(
+        (0..residuals.len()).map(|i| residuals[i] / ((1. - h[i]) * s2[i].sqrt())),
+    );
+    let xtx_inv_diag_sqrt = xtx_inv.diag().mapv(f64::sqrt);
+    x.dot(&xtx_inv) * scale.view().insert_axis(Axis(1)) / xtx_inv_diag_sqrt
+}
+
+/// Computes the partial residuals (component plus residual) of feature j: e_i + B_j x_ij, where
+/// e = y - X B. Plotted against column j they reveal nonlinearity in that feature's effect.
+pub fn partial_residuals(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    coefficients: &Array1<f64>,
+    feature_index: usize,
+) -> Array1<f64> {
+    assert!(
+        feature_index < x.shape()[1],
+        "feature_index must refer to a column of x"
+    );
+    let mut partial = y - &x.dot(coefficients);
+    partial.scaled_add(coefficients[feature_index], &x.column(feature_index));
+    partial
+}
+
+/// Computes predictions X B using only the columns whose coefficients exceed `tol` in absolute
+/// value (default: 0, i.e. skipping exact zeros), which is much cheaper than the dense product
+/// for sparse (e.g. lasso) coefficients of wide models.
+pub fn predict_sparse(
+    x: &Array2<f64>,
+    coefficients: &Array1<f64>,
+    tol: Option<f64>,
+) -> Array1<f64> {
+    let tol = tol.unwrap_or(0.);
+    assert_eq!(
+        x.shape()[1],
+        coefficients.len(),
+        "one coefficient per feature is required"
+    );
+    let mut predictions = Array1::<f64>::zeros(x.shape()[0]);
+    for (j, coefficient) in coefficients.iter().enumerate() {
+        if coefficient.abs() > tol {
+            predictions.scaled_add(*coefficient, &x.column(j));
         }
     }
-    coefficients
+    predictions
+}
+
+/// Computes the Chow test for a structural break at `break_index`, i.e. whether the coefficients
+/// of rows [0, break_index) and [break_index, n) differ. Returns the F-statistic
+/// ((RSS_pooled - RSS_1 - RSS_2) / k) / ((RSS_1 + RSS_2) / (n - 2k)) and its p-value under the
+/// F(k, n - 2k) distribution.
+pub fn chow_test(y: &Array1<f64>, x: &Array2<f64>, break_index: usize) -> (f64, f64) {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    assert!(
+        break_index > n_features && n_samples - break_index > n_features,
+        "both subsamples need more observations than features"
+    );
+    let rss = |y: &Array1<f64>, x: &Array2<f64>| solve_ols_with_stats(y, x, None, None).rss;
+    let rss_pooled = rss(y, x);
+    let rss_first = rss(
+        &y.slice(s![..break_index]).to_owned(),
+        &x.slice(s![..break_index, ..]).to_owned(),
+    );
+    let rss_second = rss(
+        &y.slice(s![break_index..]).to_owned(),
+        &x.slice(s![break_index.., ..]).to_owned(),
+    );
+    let rss_split = rss_first + rss_second;
+    let (df1, df2) = (n_features as f64, (n_samples - 2 * n_features) as f64);
+    let f_statistic = ((rss_pooled - rss_split) / df1) / (rss_split / df2);
+    (f_statistic, f_sf(f_statistic, df1, df2))
+}
+
+/// Computes Ramsey's RESET test for functional form misspecification: the model is augmented
+/// with the given `powers` (e.g. [2, 3]) of the fitted values X B and their joint significance
+/// is tested. Returns the F-statistic ((RSS - RSS_aug) / q) / (RSS_aug / (n - k - q)) and its
+/// p-value under the F(q, n - k - q) distribution, where q is the number of powers.
+///
+/// The restricted RSS is taken from the supplied `coefficients`, which should be the OLS fit of
+/// `y` on `x`. Fitted values are rescaled (which leaves the test unchanged) to keep the higher
+/// powers well conditioned.
+pub fn reset_test(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    coefficients: &Array1<f64>,
+    powers: &[i32],
+) -> (f64, f64) {
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    let n_powers = powers.len();
+    assert!(
+        n_powers > 0,
+        "at least one power of the fitted values is required"
+    );
+    assert!(
+        powers.iter().all(|p| *p >= 2),
+        "powers of the fitted values must be at least 2"
+    );
+    assert!(
+        n_samples > n_features + n_powers,
+        "the augmented model needs more observations than regressors"
+    );
+    let y_hat = x.dot(coefficients);
+    let rss = (y - &y_hat).mapv(|r| r * r).sum();
+    let scale = y_hat
+        .fold(0., |acc: f64, v| acc.max(v.abs()))
+        .max(f64::MIN_POSITIVE);
+    let mut augmented = Array2::<f64>::zeros((n_samples, n_features + n_powers));
+    augmented.slice_mut(s![.., ..n_features]).assign(x);
+    for (j, power) in powers.iter().enumerate() {
+        augmented
+            .column_mut(n_features + j)
+            .assign(&y_hat.mapv(|v| (v / scale).powi(*power)));
+    }
+    let rss_augmented = solve_ols_with_stats(y, &augmented, None, None).rss;
+    let (df1, df2) = (n_powers as f64, (n_samples - n_features - n_powers) as f64);
+    let f_statistic = ((rss - rss_augmented) / df1) / (rss_augmented / df2);
+    (f_statistic, f_sf(f_statistic, df1, df2))
+}
+
+/// Computes the recursive residuals of an OLS fit, i.e. the standardized one-step-ahead
+/// prediction errors of an expanding fit initialized on the first k rows:
+/// w_t = (y_t - x_t^T b_{t-1}) / sqrt(1 + x_t^T inv(X_{t-1}^T X_{t-1}) x_t), for t = k..n.
+///
+/// Under stable coefficients and i.i.d. noise they are independent with the noise variance.
+/// inv(X^T X) is propagated with rank one updates (see [`update_xtx_inv`]), so this takes a
+/// single pass over the data.
+pub fn recursive_residuals(y: &Array1<f64>, x: &Array2<f64>) -> Array1<f64> {
+    expanding_recursive_residuals(y, x, x.shape()[1])
+}
+
+/// Recursive residuals of an expanding fit initialized on the first `n_init` rows.
+fn expanding_recursive_residuals(y: &Array1<f64>, x: &Array2<f64>, n_init: usize) -> Array1<f64> {
+    let n_samples = x.shape()[0];
+    assert!(
+        n_init >= x.shape()[1] && n_init < n_samples,
+        "the initial fit needs at least k rows and must leave rows to predict"
+    );
+    let (y_init, x_init) = (y.slice(s![..n_init]), x.slice(s![..n_init, ..]));
+    let mut xtx_inv = inv(&x_init.t().dot(&x_init), true, None);
+    let mut xty = x_init.t().dot(&y_init);
+    let mut residuals = Array1::<f64>::zeros(n_samples - n_init);
+    for t in n_init..n_samples {
+        let x_t = x.row(t);
+        let coefficients = xtx_inv.dot(&xty);
+        residuals[t - n_init] =
+            (y[t] - x_t.dot(&coefficients)) / (1. + x_t.dot(&xtx_inv.dot(&x_t))).sqrt();
+        xtx_inv = sherman_morrison_update(&xtx_inv, &x_t, &x_t);
+        xty = xty + &x_t * y[t];
+    }
+    residuals
+}
+
+/// Computes the OLS-CUSUM stability test of Brown, Durbin & Evans (1975).
+///
+/// The recursive residuals (see [`recursive_residuals`]) of an expanding fit initialized on the
+/// first `window_size` rows (at least k) are accumulated, and their cumulative sum W_t is
+/// scaled by their standard deviation. Under stable coefficients W_t stays within the 5%
+/// bounds +/- 0.948 * (sqrt(m) + 2 * (t - window_size) / sqrt(m)), m = n - window_size.
+///
+/// # Returns
+///
+/// A tuple of the scaled CUSUM and the (upper) bound for each row, both NaN for the first
+/// `window_size` rows, and the first row at which the CUSUM breaches the bounds, if any.
+pub fn cusum_residuals(
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    window_size: usize,
+) -> (Array1<f64>, Array1<f64>, Option<usize>) {
+    const CRITICAL_VALUE: f64 = 0.948; // 5% significance level
+    let (n_samples, n_features) = (x.shape()[0], x.shape()[1]);
+    assert!(
+        window_size >= n_features && window_size + 2 < n_samples,
+        "window_size must be at least the number of features and leave some rows to monitor"
+    );
+    let residuals = expanding_recursive_residuals(y, x, window_size);
+    let m = residuals.len() as f64;
+    let sigma = residuals.std(1.);
+
+    let mut cusum = Array1::from_elem(n_samples, f64::NAN);
+    let mut bound = Array1::from_elem(n_samples, f64::NAN);
+    let mut first_breach = None;
+    let mut sum = 0.;
+    for (j, w) in residuals.iter().enumerate() {
+        let t = window_size + j;
+        sum += w;
+        cusum[t] = sum / sigma;
+        bound[t] = CRITICAL_VALUE * (m.sqrt() + 2. * j as f64 / m.sqrt());
+        if first_breach.is_none() && cusum[t].abs() > bound[t] {
+            first_breach = Some(t);
+        }
+    }
+    (cusum, bound, first_breach)
+}
+
+/// Computes the t-statistics coef / std_errors of the coefficients. Zero standard errors give
+/// +/- infinity (or NaN for a zero coefficient).
+pub fn ols_t_statistics(coef: &Array1<f64>, std_errors: &Array1<f64>) -> Array1<f64> {
+    assert_eq!(
+        coef.len(),
+        std_errors.len(),
+        "one standard error per coefficient is required"
+    );
+    coef / std_errors
+}
+
+/// Computes two-sided p-values 2 * P(T > |t|) of t-statistics under Student's t distribution
+/// with `dof` degrees of freedom. Returns NaN for `dof = 0`, where the distribution is undefined,
+/// and for NaN t-statistics.
+pub fn ols_p_values(t_stats: &Array1<f64>, dof: usize) -> Array1<f64> {
+    if dof == 0 {
+        return Array1::from_elem(t_stats.len(), f64::NAN);
+    }
+    t_stats.mapv(|t| {
+        if t.is_nan() {
+            f64::NAN
+        } else {
+            2. * student_t_cdf(-t.abs(), dof as f64)
+        }
+    })
+}
+
+/// Computes confidence intervals coef +/- t * std_errors at the given confidence `level`
+/// (e.g. 0.95), with t the Student's t critical value for `dof` degrees of freedom.
+///
+/// # Returns
+///
+/// A tuple of the lower and upper bounds of each coefficient.
+pub fn ols_confidence_intervals(
+    coef: &Array1<f64>,
+    std_errors: &Array1<f64>,
+    dof: usize,
+    level: f64,
+) -> (Array1<f64>, Array1<f64>) {
+    assert!(
+        level > 0. && level < 1.,
+        "level must be strictly between 0 and 1"
+    );
+    assert!(dof > 0, "the degrees of freedom must be positive");
+    assert_eq!(
+        coef.len(),
+        std_errors.len(),
+        "one standard error per coefficient is required"
+    );
+    let critical_value = student_t_ppf(0.5 + 0.5 * level, dof as f64);
+    let half_width = std_errors * critical_value;
+    (coef - &half_width, coef + &half_width)
+}
+
+/// Box-Cox transform (y^lambda - 1) / lambda of strictly positive targets (log y for lambda = 0).
+fn boxcox_transform(y: &Array1<f64>, lambda: f64) -> Array1<f64> {
+    if lambda.abs() < 1e-12 {
+        y.mapv(f64::ln)
+    } else {
+        y.mapv(|y_i| (y_i.powf(lambda) - 1.) / lambda)
+    }
+}
+
+/// Solves OLS on Box-Cox transformed, strictly positive, targets.
+///
+/// If `lambda` is not provided, it is chosen on the grid -2, -1.95, ..., 2 by maximizing the
+/// profile log-likelihood -n / 2 * log(RSS(lambda) / n) + (lambda - 1) * sum_i log(y_i), where
+/// the second term is the log Jacobian of the transform. With lambda = 1 the targets are only
+/// shifted by -1, so this reproduces OLS up to the intercept.
+///
+/// # Returns
+///
+/// A tuple of the coefficients (on the transformed scale) and the lambda used.
+pub fn solve_boxcox(y: &Array1<f64>, x: &Array2<f64>, lambda: Option<f64>) -> (Array1<f64>, f64) {
+    assert!(
+        y.iter().all(|y_i| *y_i > 0.),
+        "the Box-Cox transform requires strictly positive targets"
+    );
+    let lambda = lambda.unwrap_or_else(|| {
+        let n_samples = y.len() as f64;
+        let sum_log_y = y.mapv(f64::ln).sum();
+        let profile_log_likelihood = |lambda: f64| {
+            let rss = solve_ols_with_stats(&boxcox_transform(y, lambda), x, None, None).rss;
+            -0.5 * n_samples * (rss / n_samples).ln() + (lambda - 1.) * sum_log_y
+        };
+        (0..=80)
+            .map(|i| -2. + 0.05 * i as f64)
+            .map(|lambda| (lambda, profile_log_likelihood(lambda)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+            .0
+    });
+    (
+        solve_ols(&boxcox_transform(y, lambda), x, None, None),
+        lambda,
+    )
 }
@@ -7,11 +7,38 @@ use pyo3::{pymodule, PyResult, Python};
 mod tests {
     use crate::expressions::{convert_polars_to_ndarray, NullPolicy};
     use crate::least_squares::{
-        inv, outer_product, solve_elastic_net, solve_ols, solve_recursive_least_squares,
-        solve_ridge, solve_rolling_ols, update_xtx_inv, woodbury_update, SolveMethod,
+        add_intercept, add_intercept_inplace, akaike_weights, as_coefficient_table,
+        blocked_cv_folds, blocked_cv_score, bootstrap_t_ci, check_feature_scaling, chow_test,
+        compute_qr, covratio, cusum_residuals, default_alpha_grid, degrees_of_freedom,
+        design_condition_number, design_rank, detect_separation, dfbetas, dffits,
+        drop_invalid_rows, flag_outliers, glm_profile_ci, gls_coef_covariance, gram_eigenvalues,
+        inv, jackknife_se, lasso_path_df, leverage, leverage_qr, mad_standardized_residuals,
+        ols_coef_covariance, ols_confidence_intervals, ols_loocv, ols_p_values,
+        ols_robust_covariance, ols_standard_errors, ols_t_statistics, ols_vs_gls_efficiency,
+        oos_r_squared, outer_product, overall_significance_robust, partial_residuals,
+        polynomial_features, predict_sparse, r_squared, recursive_residuals, reset_test,
+        residual_acf, ridge_condition_number, ridge_effective_df, ridge_gcv, ridge_hat_trace,
+        ridge_leverage, ridge_marginal_likelihood, robust_estimator_se, rolling_effective_df,
+        rolling_prediction_intervals, sequential_orthogonalize, sherman_morrison_update,
+        singular_values, solve_ard_ridge, solve_boxcox, solve_constrained_ridge, solve_elastic_net,
+        solve_elastic_net_generic, solve_elastic_net_path, solve_elastic_net_path_weighted,
+        solve_elastic_net_with_intercept, solve_elastic_net_with_penalty_matrix,
+        solve_fused_rolling_ridge, solve_gtls, solve_huber, solve_huber_with_weights,
+        solve_logistic_firth, solve_min_norm_interpolate, solve_nnls, solve_ols, solve_ols_cg,
+        solve_ols_generic, solve_ols_lsqr, solve_ols_multi, solve_ols_with_stats, solve_pcr,
+        solve_pcr_incremental, solve_quantile, solve_quasibinomial, solve_recursive_least_squares,
+        solve_recursive_least_squares_independent, solve_recursive_least_squares_with_predictions,
+        solve_recursive_least_squares_with_variances, solve_ridge, solve_ridge_dual,
+        solve_ridge_generic, solve_ridge_path, solve_ridge_path_cg, solve_ridge_with_intercept,
+        solve_rolling_elastic_net, solve_rolling_ols, solve_rolling_ols_with_state,
+        solve_segmented, solve_segmented_search, solve_tls, solve_trimmed_ols,
+        studentized_residuals, tikhonov_effective_df, tikhonov_gcv, update_xtx_inv,
+        variance_inflation_factors, wald_test, woodbury_update, CovarianceType, FitType, GlmFamily,
+        IncrementalPca, PsiFunction, RecursiveLeastSquares, RecursiveRidge, RunningMoments,
+        SolveMethod,
     };
     use ndarray::prelude::*;
-    use ndarray_linalg::assert_close_l2;
+    use ndarray_linalg::close_l2;
     use ndarray_rand::rand_distr::Normal;
     use ndarray_rand::RandomExt;
     use polars::datatypes::DataType::Float64;
@@ -42,8 +69,8 @@ mod tests {
         let coefficients_1 = solve_ols(&targets, &features, None, None);
         let coefficients_2 = solve_ols(&targets, &features, Some(SolveMethod::SVD), None);
         let expected = array![1., 1.];
-        assert_close_l2!(&coefficients_1, &coefficients_2, 0.001);
-        assert_close_l2!(&coefficients_1, &expected, 0.001);
+        close_l2(&coefficients_1, &coefficients_2, 0.001);
+        close_l2(&coefficients_1, &expected, 0.001);
     }
 
     #[test]
@@ -52,8 +79,8 @@ mod tests {
         let coefficients_1 = solve_ridge(&targets, &features, 10.0, None, None);
         let coefficients_2 = solve_ridge(&targets, &features, 10.0, Some(SolveMethod::SVD), None);
         let expected = array![0.999, 0.999];
-        assert_close_l2!(&coefficients_1, &coefficients_2, 0.001);
-        assert_close_l2!(&coefficients_1, &expected, 0.001);
+        close_l2(&coefficients_1, &coefficients_2, 0.001);
+        close_l2(&coefficients_1, &expected, 0.001);
     }
 
     #[test]
@@ -68,20 +95,29 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
         );
         let expected = array![0.999, 0.999];
-        assert_close_l2!(&coefficients, &expected, 0.001);
+        close_l2(&coefficients, &expected, 0.001);
     }
 
     #[test]
     fn test_recursive_least_squares() {
         let (targets, features) = make_data(None);
-        let coefficients =
-            solve_recursive_least_squares(&targets, &features, Some(252.0), Some(0.01), None);
+        let is_valid = vec![true; targets.len()];
+        let coefficients = solve_recursive_least_squares(
+            &targets,
+            &features,
+            Some(252.0),
+            Some(0.01),
+            None,
+            &is_valid,
+        );
         let expected = array![1.0, 1.0];
         println!("{:?}", coefficients.slice(s![0, ..]));
         println!("{:?}", coefficients.slice(s![-1, ..]));
-        assert_close_l2!(&coefficients.slice(s![-1, ..]), &expected, 0.0001);
+        close_l2(&coefficients.slice(s![-1, ..]), &expected, 0.0001);
     }
 
     #[test]
@@ -94,24 +130,74 @@ mod tests {
             Some(100usize),
             Some(false),
             None,
+            None,
+            None,
+            None,
         );
         let expected: Array1<f64> = array![1.0, 1.0];
         println!("{:?}", coefficients.slice(s![0, ..]));
         println!("{:?}", coefficients.slice(s![-1, ..]));
-        assert_close_l2!(&coefficients.slice(s![-1, ..]), &expected, 0.0001);
+        close_l2(&coefficients.slice(s![-1, ..]), &expected, 0.0001);
+    }
+
+    #[test]
+    fn test_rolling_least_squares_with_state() {
+        let (targets, features) = make_data(None);
+        let (targets, features) = (
+            targets.slice(s![..500]).to_owned(),
+            features.slice(s![..500, ..]).to_owned(),
+        );
+        for use_woodbury in [false, true] {
+            let expected = solve_rolling_ols(
+                &targets,
+                &features,
+                100usize,
+                Some(10usize),
+                Some(use_woodbury),
+                None,
+                None,
+                None,
+                None,
+            );
+            // split into a short batch (still warming up), and two batches after warm-up
+            let mut state = None;
+            let mut batches = Vec::new();
+            for (start, end) in [(0, 5), (5, 237), (237, 500)] {
+                let (coefficients, new_state) = solve_rolling_ols_with_state(
+                    &targets.slice(s![start..end]).to_owned(),
+                    &features.slice(s![start..end, ..]).to_owned(),
+                    100usize,
+                    Some(10usize),
+                    Some(use_woodbury),
+                    None,
+                    state,
+                );
+                batches.push(coefficients);
+                state = Some(new_state);
+            }
+            let views: Vec<_> = batches.iter().map(|b| b.view()).collect();
+            let stitched = ndarray::concatenate(Axis(0), &views).unwrap();
+            assert_eq!(stitched.shape(), expected.shape());
+            assert!(stitched.slice(s![..9, ..]).iter().all(|v| v.is_nan()));
+            close_l2(
+                &stitched.slice(s![9.., ..]),
+                &expected.slice(s![9.., ..]),
+                0.00001,
+            );
+        }
     }
 
     #[test]
     fn test_woodbury_update() {
         // Test matrices
         let a = array![[0.5, 0.2], [0.0, 0.5]]; // A^{-1}
-        let a_inv = inv(&a, false);
+        let a_inv = inv(&a, false, None);
         let u = array![[1.0, 2.0], [3.0, 4.0]]; // U
         let c = array![[1.0, 0.0], [0.0, 1.0]]; // C
         let v = array![[1.0, 0.0], [0.0, 1.0]]; // V
 
         // Expected result
-        let expected_result = inv(&(&a + &u.dot(&c).dot(&v)), false);
+        let expected_result = inv(&(&a + &u.dot(&c).dot(&v)), false, None);
 
         // Compute the Woodbury update
         let result = woodbury_update(&a_inv, &u, &c, &v, Some(true));
@@ -119,7 +205,7 @@ mod tests {
         // test confirms: inv(A + UCV) == A{-1} - A^{-1} U (C^{-1} + V A^{-1} U)^{-1} V A^{-1}
 
         // Compare with expected result
-        assert_close_l2!(&result, &expected_result, 0.00001);
+        close_l2(&result, &expected_result, 0.00001);
     }
 
     #[test]
@@ -128,7 +214,7 @@ mod tests {
         let x = Array2::<f64>::random((252, 5), Normal::new(0., 1.).unwrap());
 
         let xtx = x.t().dot(&x);
-        let mut xtx_inv = inv(&xtx, true);
+        let mut xtx_inv = inv(&xtx, true, None);
 
         let x_new = array![0.5, 2., -0.3, 0.1, 0.2];
         let x_new = x_new.view(); // new data point
@@ -146,8 +232,2795 @@ mod tests {
         let expected = inv(
             &(&xtx - &outer_product(&x_old, &x_old) + &outer_product(&x_new, &x_new)),
             true,
+            None,
+        );
+        close_l2(&xtx_inv, &expected, 0.00001);
+    }
+
+    #[test]
+    fn test_ridge_hat_trace() {
+        let (_, features) = make_data(None);
+        for alpha in [0.0, 1.0, 1_000.0, 100_000.0] {
+            let trace = ridge_hat_trace(&features, alpha);
+            let expected = ridge_effective_df(&features, alpha);
+            assert!((trace - expected).abs() < 1e-8, "{trace} != {expected}");
+        }
+        // the trace equals the number of features when un-penalized, and shrinks with alpha
+        assert!((ridge_hat_trace(&features, 0.0) - 2.0).abs() < 1e-10);
+        assert!(ridge_hat_trace(&features, 100_000.0) < ridge_hat_trace(&features, 1.0));
+    }
+
+    #[test]
+    fn test_lasso_path_df() {
+        let x = Array2::<f64>::random((1_000, 3), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![2.0, 0.5, 0.0]);
+        let alphas = [0.001, 0.1, 1.0, 3.0];
+        let mut path = Array2::<f64>::zeros((alphas.len(), 3));
+        for (i, alpha) in alphas.iter().enumerate() {
+            let coefficients = solve_elastic_net(
+                &y,
+                &x,
+                *alpha,
+                Some(1.0),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            path.row_mut(i).assign(&coefficients);
+        }
+        let df = lasso_path_df(&path, None);
+        assert_eq!(df.len(), alphas.len());
+        // degrees of freedom can only decrease as alpha increases
+        assert!(df.windows(2).into_iter().all(|w| w[0] >= w[1]));
+        assert_eq!(df[df.len() - 1], 0);
+        assert!(df[0] >= 2);
+    }
+
+    #[test]
+    fn test_bootstrap_t_ci() {
+        use ndarray_rand::rand::rngs::StdRng;
+        use ndarray_rand::rand::{Rng, SeedableRng};
+        use ndarray_rand::rand_distr::Exp;
+
+        // skewed (centered exponential) errors on a small sample, where the intercept estimate
+        // is itself skewed and the percentile interval undercovers
+        let mut rng = StdRng::seed_from_u64(0);
+        let true_coefficients = array![1.0, -0.5];
+        let (n_samples, n_boot) = (15, 200);
+        let (mut n_covered, mut n_covered_percentile) = (0, 0);
+        let n_trials = 200;
+        for trial in 0..n_trials {
+            let x = add_intercept(&Array2::<f64>::random_using(
+                (n_samples, 1),
+                Normal::new(0., 1.).unwrap(),
+                &mut rng,
+            ));
+            let noise =
+                Array1::<f64>::random_using(n_samples, Exp::new(1.).unwrap(), &mut rng) - 1.0;
+            let y = x.dot(&true_coefficients) + noise;
+            let (lower, upper) = bootstrap_t_ci(&y, &x, n_boot, 0.9, Some(trial));
+            assert!(lower.iter().zip(upper.iter()).all(|(l, u)| l < u));
+            n_covered += (lower[0] <= 1.0 && 1.0 <= upper[0]) as usize;
+
+            // percentile interval from the bootstrap distribution of the intercept itself
+            let mut resample_rng = StdRng::seed_from_u64(trial);
+            let mut intercepts: Vec<f64> = (0..n_boot)
+                .map(|_| {
+                    let indices: Vec<usize> = (0..n_samples)
+                        .map(|_| resample_rng.gen_range(0..n_samples))
+                        .collect();
+                    let x_b = x.select(Axis(0), &indices);
+                    solve_ols(&y.select(Axis(0), &indices), &x_b, None, None)[0]
+                })
+                .collect();
+            intercepts.sort_by(|a, b| a.total_cmp(b));
+            let quantile = |q: f64| intercepts[((n_boot - 1) as f64 * q).round() as usize];
+            n_covered_percentile += (quantile(0.05) <= 1.0 && 1.0 <= quantile(0.95)) as usize;
+        }
+        let coverage = n_covered as f64 / n_trials as f64;
+        let coverage_percentile = n_covered_percentile as f64 / n_trials as f64;
+        assert!(
+            coverage >= 0.8,
+            "coverage {coverage} too far below nominal 0.9"
+        );
+        assert!(
+            (coverage - 0.9).abs() < (coverage_percentile - 0.9).abs(),
+            "bootstrap-t coverage {coverage} not closer to nominal 0.9 than percentile coverage \
+             {coverage_percentile}"
+        );
+
+        // an exact fit has vanishing bootstrap standard errors, leaving no finite t*
+        let (lower, upper) =
+            bootstrap_t_ci(&Array1::zeros(10), &Array2::ones((10, 1)), 50, 0.9, Some(0));
+        assert!(lower[0].is_nan() && upper[0].is_nan());
+    }
+
+    #[test]
+    fn test_add_intercept() {
+        let x = array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]];
+        let augmented = add_intercept(&x);
+        assert_eq!(augmented.shape(), &[3, 3]);
+        assert!(augmented.column(0).iter().all(|v| *v == 1.0));
+        assert_eq!(augmented.slice(s![.., 1..]), x);
+
+        let mut x_inplace = x.clone();
+        add_intercept_inplace(&mut x_inplace);
+        assert_eq!(x_inplace, augmented);
+    }
+
+    #[test]
+    fn test_wald_test() {
+        let x = Array2::<f64>::random((500, 3), Normal::new(0., 1.).unwrap());
+        let noise = Array1::<f64>::random(500, Normal::new(0., 1.).unwrap());
+        // heteroskedastic noise, scaling with the magnitude of the first feature
+        let y = x.dot(&array![1.0, 0.0, 0.0]) + &noise * &x.column(0).mapv(|v| 1. + v.abs());
+        let coefficients = solve_ols(&y, &x, None, None);
+        let residuals = &y - &x.dot(&coefficients);
+        let cov = ols_robust_covariance(&x, &residuals, CovarianceType::HC3);
+
+        let (chi2, df, p_value) = wald_test(&coefficients, &cov, &[1, 2]);
+        assert_eq!(df, 2);
+        assert!(chi2 >= 0.);
+        assert!(p_value > 0.001, "zero coefficients rejected: p={p_value}");
+
+        let (_, df, p_value) = wald_test(&coefficients, &cov, &[0]);
+        assert_eq!(df, 1);
+        assert!(
+            p_value < 1e-6,
+            "non-zero coefficient not rejected: p={p_value}"
+        );
+    }
+
+    #[test]
+    fn test_recursive_ridge() {
+        let x1 = Array1::<f64>::random(2_000, Normal::new(0., 1.).unwrap());
+        let x2 = &x1 + &Array1::<f64>::random(2_000, Normal::new(0., 0.01).unwrap());
+        let x = ndarray::stack(Axis(1), &[x1.view(), x2.view()]).unwrap();
+        let y = &x1 + &x2 + &Array1::<f64>::random(2_000, Normal::new(0., 0.1).unwrap());
+
+        // near-collinear features and an uninformative prior make early RLS estimates explode
+        let mut rls = RecursiveLeastSquares::new(2, 1.0e6, None, None);
+        let mut ridge = RecursiveRidge::new(2, 1.0, None);
+        let (mut max_norm_rls, mut max_norm_ridge) = (0.0f64, 0.0f64);
+        for t in 0..2_000 {
+            let x_t = x.row(t).to_owned();
+            rls.update(&x_t, y[t]);
+            ridge.update(&x_t, y[t]);
+            if t < 10 {
+                max_norm_ridge = max_norm_ridge.max(ridge.coefficients().dot(ridge.coefficients()));
+                max_norm_rls = max_norm_rls.max(rls.coefficients().dot(rls.coefficients()));
+            }
+        }
+        assert!(max_norm_ridge < 10.0, "early ridge coefficients unstable");
+        assert!(max_norm_ridge < max_norm_rls);
+        // with enough data the penalty becomes negligible
+        close_l2(ridge.coefficients(), &array![1.0, 1.0], 0.1);
+        assert!((ridge.predict(&array![1.0, 1.0]) - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_check_feature_scaling() {
+        let (_, features) = make_data(None);
+        assert!(check_feature_scaling(&features, None).is_none());
+
+        // scale the second column up by a large factor and append a constant column
+        let mut badly_scaled = add_intercept(&features);
+        badly_scaled.column_mut(2).mapv_inplace(|v| v * 1.0e4);
+        let warning = check_feature_scaling(&badly_scaled, None);
+        assert!(warning.is_some());
+        assert!(warning.unwrap().contains("standardizing"));
+        assert!(check_feature_scaling(&badly_scaled, Some(1.0e5)).is_none());
+    }
+
+    #[test]
+    fn test_covratio() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (200, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let mut y = x.dot(&array![0.5, 1.0, -1.0])
+            + Array1::<f64>::random(200, Normal::new(0., 0.1).unwrap());
+        y[17] += 5.0; // outlier
+        let coefficients = solve_ols(&y, &x, None, None);
+        let ratios = covratio(&y, &x, &coefficients);
+        assert_eq!(ratios.len(), 200);
+        assert!(
+            ratios[17] < 0.5,
+            "outlier COVRATIO {} not below 1",
+            ratios[17]
+        );
+        let median = {
+            let mut sorted = ratios.to_vec();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            sorted[100]
+        };
+        assert!((median - 1.0).abs() < 0.05);
+
+        let t = studentized_residuals(&y, &x, &coefficients);
+        assert!(t[17] > 10.0);
+    }
+
+    #[test]
+    fn test_polynomial_features() {
+        let x = Array2::<f64>::random((50, 4), Normal::new(0., 1.).unwrap());
+
+        let full = polynomial_features(&x, 2, false, true);
+        assert_eq!(full.shape(), &[50, 1 + 4 + 10]);
+        assert!(full.column(0).iter().all(|v| *v == 1.));
+        close_l2(&full.column(5), &(&x.column(0) * &x.column(0)), 1e-12);
+
+        let interactions = polynomial_features(&x, 2, true, false);
+        // originals plus C(4, 2) cross products
+        assert_eq!(interactions.shape(), &[50, 4 + 6]);
+        close_l2(&interactions.slice(s![.., ..4]), &x, 1e-12);
+        let mut k = 4;
+        for i in 0..4 {
+            for j in (i + 1)..4 {
+                let expected = &x.column(i) * &x.column(j);
+                close_l2(&interactions.column(k), &expected, 1e-12);
+                k += 1;
+            }
+        }
+        // no squared terms
+        for k in 4..10 {
+            for i in 0..4 {
+                let square = &x.column(i) * &x.column(i);
+                let diff = &interactions.column(k) - &square;
+                assert!(diff.dot(&diff) > 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_constrained_ridge() {
+        let x = Array2::<f64>::random((200, 4), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![0.5, 0.3, 0.1, -0.2])
+            + Array1::<f64>::random(200, Normal::new(0., 0.1).unwrap());
+        // fully invested & first two weights equal
+        let a = array![[1., 1., 1., 1.], [1., -1., 0., 0.]];
+        let b = array![1., 0.];
+
+        let coefficients = solve_constrained_ridge(&y, &x, &a, &b, 10.0);
+        close_l2(&a.dot(&coefficients), &b, 1e-10);
+
+        // alpha = 0 matches the closed form equality constrained OLS solution
+        let xtx_inv = inv(&x.t().dot(&x), true, None);
+        let ols = solve_ols(&y, &x, None, None);
+        let a_xtx_inv_at = a.dot(&xtx_inv).dot(&a.t());
+        let multiplier = inv(&a_xtx_inv_at, false, None).dot(&(a.dot(&ols) - &b));
+        let expected = &ols - &xtx_inv.dot(&a.t()).dot(&multiplier);
+        let unpenalized = solve_constrained_ridge(&y, &x, &a, &b, 0.0);
+        close_l2(&unpenalized, &expected, 1e-8);
+        close_l2(&a.dot(&unpenalized), &b, 1e-10);
+
+        // the penalty shrinks within the feasible set
+        assert!(coefficients.dot(&coefficients) < unpenalized.dot(&unpenalized));
+    }
+
+    #[test]
+    fn test_ridge_leverage() {
+        let x = Array2::<f64>::random((100, 5), Normal::new(0., 1.).unwrap());
+        close_l2(&ridge_leverage(&x, 1e-10), &leverage(&x), 1e-8);
+
+        let alpha = 5.0;
+        let h = ridge_leverage(&x, alpha);
+        assert!((h.sum() - ridge_hat_trace(&x, alpha)).abs() < 1e-10);
+        let ridge_matrix = x.t().dot(&x) + Array2::<f64>::eye(5) * alpha;
+        let expected = (&x.dot(&inv(&ridge_matrix, true, None)) * &x).sum_axis(Axis(1));
+        close_l2(&h, &expected, 1e-10);
+        assert!(h.iter().zip(leverage(&x).iter()).all(|(r, o)| r < o));
+    }
+
+    #[test]
+    fn test_elastic_net_feature_mask() {
+        let x = Array2::<f64>::random((200, 4), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1.0, -0.5, 0.25, 2.0])
+            + Array1::<f64>::random(200, Normal::new(0., 0.1).unwrap());
+        let mask = [true, false, true, false];
+        let coefficients = solve_elastic_net(
+            &y,
+            &x,
+            0.01,
+            Some(0.5),
+            None,
+            Some(1e-10),
+            None,
+            None,
+            Some(&mask),
+            None,
+        );
+        assert_eq!(coefficients[1], 0.);
+        assert_eq!(coefficients[3], 0.);
+
+        let x_reduced = x.select(Axis(1), &[0, 2]);
+        let reduced = solve_elastic_net(
+            &y,
+            &x_reduced,
+            0.01,
+            Some(0.5),
+            None,
+            Some(1e-10),
+            None,
+            None,
+            None,
+            None,
+        );
+        close_l2(&coefficients.select(Axis(0), &[0, 2]), &reduced, 1e-8);
+    }
+
+    #[test]
+    fn test_akaike_weights() {
+        let aics = array![1012.3, 1000.0, 1002.0, 1500.0];
+        let weights = akaike_weights(&aics);
+        assert!((weights.sum() - 1.0).abs() < 1e-12);
+        let best = weights
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+        assert_eq!(best, 1);
+        // dAIC = 2 gives a relative likelihood of exp(-1)
+        assert!((weights[2] / weights[1] - (-1.0f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_centered_rolling_least_squares() {
+        let x = Array2::<f64>::random((120, 2), Normal::new(0., 1.).unwrap());
+        let y =
+            x.dot(&array![1.0, -1.0]) + Array1::<f64>::random(120, Normal::new(0., 0.5).unwrap());
+        let window_size = 20usize;
+        let half = window_size / 2;
+        for use_woodbury in [false, true] {
+            let coefficients = solve_rolling_ols(
+                &y,
+                &x,
+                window_size,
+                Some(5usize),
+                Some(use_woodbury),
+                None,
+                Some(true),
+                None,
+                None,
+            );
+            for i in [0usize, 3, 10, 57, 110, 119] {
+                let (start, end) = (i.saturating_sub(half), (i + half).min(119));
+                let expected = solve_ols(
+                    &y.slice(s![start..=end]).to_owned(),
+                    &x.slice(s![start..=end, ..]).to_owned(),
+                    None,
+                    None,
+                );
+                close_l2(&coefficients.row(i), &expected, 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolling_prediction_intervals() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (400, 1),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let mut noise = Array1::<f64>::random(400, Normal::new(0., 0.1).unwrap());
+        // the second half is much noisier
+        noise.slice_mut(s![200..]).mapv_inplace(|e| e * 10.);
+        let y = x.dot(&array![0.5, 1.0]) + &noise;
+        let (predictions, lower, upper) = rolling_prediction_intervals(&y, &x, 50, None, Some(0.9));
+        assert!(predictions.slice(s![..3]).iter().all(|p| p.is_nan()));
+        assert!(predictions.slice(s![3..]).iter().all(|p| p.is_finite()));
+        assert!(lower.iter().zip(upper.iter()).skip(3).all(|(l, u)| l < u));
+
+        let width = &upper - &lower;
+        let calm = width.slice(s![100..200]).mean().unwrap();
+        let volatile = width.slice(s![300..400]).mean().unwrap();
+        assert!(volatile > 5. * calm);
+
+        // the forecast only uses the preceding window
+        let expected = solve_ols(
+            &y.slice(s![250..300]).to_owned(),
+            &x.slice(s![250..300, ..]).to_owned(),
+            None,
+            None,
+        );
+        assert!((predictions[300] - x.row(300).dot(&expected)).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_ridge_path() {
+        let x = Array2::<f64>::random((300, 20), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&Array1::<f64>::random(20, Normal::new(0., 1.).unwrap()))
+            + Array1::<f64>::random(300, Normal::new(0., 0.5).unwrap());
+        let alphas = Array1::logspace(10., 3., -2., 12);
+
+        let path = solve_ridge_path(&y, &x, &alphas, None);
+        assert_eq!(path.shape(), &[12, 20]);
+        for (i, alpha) in alphas.iter().enumerate() {
+            let expected = solve_ridge(&y, &x, *alpha, Some(SolveMethod::Cholesky), None);
+            close_l2(&path.row(i), &expected, 1e-8);
+        }
+        let path_lu = solve_ridge_path(&y, &x, &alphas, Some(SolveMethod::LU));
+        close_l2(&path_lu, &path, 1e-8);
+
+        for warm_start in [true, false] {
+            let path_cg = solve_ridge_path_cg(&y, &x, &alphas, None, None, Some(warm_start));
+            close_l2(&path_cg, &path, 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_gram_eigenvalues() {
+        let x = Array2::<f64>::random((100, 6), Normal::new(0., 1.).unwrap());
+        let eigenvalues = gram_eigenvalues(&x);
+        assert!(eigenvalues.windows(2).into_iter().all(|w| w[0] >= w[1]));
+        let s = singular_values(&x);
+        close_l2(&eigenvalues, &(&s * &s), 1e-8);
+    }
+
+    #[test]
+    fn test_r_squared() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (200, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let y = x.dot(&array![3.0, 1.0, -1.0])
+            + Array1::<f64>::random(200, Normal::new(0., 1.).unwrap());
+        let y_hat = x.dot(&solve_ols(&y, &x, None, None));
+        let r2 = r_squared(&y, &y_hat, None, None);
+        assert!(r2 > 0. && r2 < 1.);
+        let uniform = Array1::<f64>::from_elem(200, 2.5);
+        assert!((r_squared(&y, &y_hat, Some(&uniform), None) - r2).abs() < 1e-12);
+
+        // the uncentered TSS is larger, so uncentered R^2 is higher with a non-zero mean
+        assert!(r_squared(&y, &y_hat, None, Some(false)) > r2);
+
+        // integer weights are equivalent to repeating observations
+        let weights = Array1::from_iter((0..200).map(|i| (1 + i % 3) as f64));
+        let repeats: Vec<usize> = (0..200).flat_map(|i| vec![i; 1 + i % 3]).collect();
+        let expected = r_squared(
+            &y.select(Axis(0), &repeats),
+            &y_hat.select(Axis(0), &repeats),
+            None,
+            None,
+        );
+        assert!((r_squared(&y, &y_hat, Some(&weights), None) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_as_coefficient_table() {
+        let coefficients = array![1.0, -2.0, 0.5];
+        let standard_errors = array![0.1, 0.4, 1.0];
+        let t_values = &coefficients / &standard_errors;
+        let p_values = array![0.0, 0.0, 0.62];
+        let table = as_coefficient_table(
+            &["const", "x1", "x2"],
+            &coefficients,
+            &standard_errors,
+            &t_values,
+            &p_values,
+        );
+        assert_eq!(table.names, vec!["const", "x1", "x2"]);
+        assert_eq!(table.coefficients.len(), 3);
+        assert_eq!(table.standard_errors.len(), 3);
+        assert_eq!(table.t_values, vec![10.0, -5.0, 0.5]);
+        assert_eq!(table.p_values[2], 0.62);
+        assert_eq!(table.coefficients[1], -2.0);
+    }
+
+    #[test]
+    fn test_detect_separation() {
+        use ndarray_rand::rand::rngs::StdRng;
+        use ndarray_rand::rand::SeedableRng;
+        use ndarray_rand::rand_distr::Uniform;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let x = add_intercept(&Array2::<f64>::random_using(
+            (100, 2),
+            Normal::new(0., 1.).unwrap(),
+            &mut rng,
+        ));
+        // perfectly separated by the sign of the first feature
+        let separated = x.column(1).mapv(|v| if v > 0. { 1. } else { 0. });
+        assert!(detect_separation(&separated, &x));
+        // quasi-complete separation: a tie on the boundary with both labels
+        let mut x_quasi = x.clone();
+        x_quasi.row_mut(0).assign(&x.row(1));
+        x_quasi[[0, 1]] = 0.;
+        x_quasi[[1, 1]] = 0.;
+        let mut quasi = x_quasi.column(1).mapv(|v| if v > 0. { 1. } else { 0. });
+        quasi[1] = 1.;
+        assert!(detect_separation(&quasi, &x_quasi));
+
+        // overlapping classes: labels drawn from a logistic model
+        let noise = Array1::<f64>::random_using(100, Normal::new(0., 2.).unwrap(), &mut rng);
+        let overlapping = (&x.column(1) + &noise).mapv(|v| if v > 0. { 1. } else { 0. });
+        assert!(!detect_separation(&overlapping, &x));
+
+        // unscaled overlapping features: the MLE is finite, although its linear predictor
+        // reaches |x_i^T w| of about 50
+        let x = add_intercept(&Array2::<f64>::random_using(
+            (1000, 1),
+            Uniform::new(-100., 100.),
+            &mut rng,
+        ));
+        let u = Array1::<f64>::random_using(1000, Uniform::new(0., 1.), &mut rng);
+        let probabilities = x.column(1).mapv(|v| 1. / (1. + (-0.5 * v).exp()));
+        let y = Array1::from_iter(u.iter().zip(probabilities.iter()).map(|(u_i, p_i)| {
+            if u_i < p_i {
+                1.
+            } else {
+                0.
+            }
+        }));
+        assert!(!detect_separation(&y, &x));
+    }
+
+    #[test]
+    fn test_logistic_firth() {
+        // intercept only with all successes: the Firth estimate is logit((n + 0.5) / (n + 1))
+        let x = Array2::<f64>::ones((10, 1));
+        let y = Array1::<f64>::ones(10);
+        let coefficients = solve_logistic_firth(&y, &x, None, None);
+        assert!((coefficients[0] - 21f64.ln()).abs() < 1e-6);
+
+        // small separated dataset: plain logistic regression diverges, Firth stays finite
+        let x = add_intercept(&array![
+            [-2.0],
+            [-1.5],
+            [-1.0],
+            [-0.5],
+            [0.5],
+            [1.0],
+            [1.5],
+            [2.0]
+        ]);
+        let y = array![0., 0., 0., 0., 1., 1., 1., 1.];
+        assert!(detect_separation(&y, &x));
+        let coefficients = solve_logistic_firth(&y, &x, None, None);
+        assert!(coefficients.iter().all(|c| c.is_finite()));
+        assert!(coefficients[1] > 0. && coefficients[1] < 10.);
+        assert!(coefficients[0].abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pcr() {
+        let x = Array2::<f64>::random((200, 4), Normal::new(2., 1.).unwrap());
+        let y = x.dot(&array![1.0, 0.5, -0.5, 0.25])
+            + Array1::<f64>::random(200, Normal::new(0., 0.1).unwrap());
+        let ordinary = solve_pcr(&y, &x, 2, None);
+        let uniform = Array1::<f64>::from_elem(200, 3.);
+        close_l2(&solve_pcr(&y, &x, 2, Some(&uniform)), &ordinary, 1e-10);
+
+        // all components recover the OLS slopes of a fit with an intercept
+        let full = solve_pcr(&y, &x, 4, None);
+        let ols = solve_ols(&y, &add_intercept(&x), None, None);
+        close_l2(&full, &ols.slice(s![1..]), 1e-8);
+
+        // recency weights change the fit, and weighted PCR with all components is WLS
+        let weights = Array1::from_iter((0..200).map(|i| 0.99f64.powi(200 - i)));
+        let weighted = solve_pcr(&y, &x, 4, Some(&weights));
+        let sqrt_w = weights.mapv(f64::sqrt);
+        let wls = solve_ols(
+            &(&y * &sqrt_w),
+            &(&add_intercept(&x) * &sqrt_w.view().insert_axis(Axis(1))),
+            None,
+            None,
+        );
+        close_l2(&weighted, &wls.slice(s![1..]), 1e-8);
+    }
+
+    #[test]
+    fn test_inv_ridge_floor() {
+        // rank one gram matrix
+        let x = array![[1., 2.], [2., 4.], [3., 6.]];
+        let xtx = x.t().dot(&x);
+        let floored = inv(&xtx, true, Some(1e-6));
+        assert!(floored.iter().all(|v| v.is_finite()));
+        let floor = 1e-6 * xtx.diag().sum() / 2.;
+        let identity = floored.dot(&(&xtx + &(Array2::<f64>::eye(2) * floor)));
+        close_l2(&identity, &Array2::<f64>::eye(2), 1e-6);
+        // no floor leaves well conditioned inverses unchanged
+        let a = array![[2., 0.5], [0.5, 1.]];
+        close_l2(&inv(&a, true, None).dot(&a), &Array2::<f64>::eye(2), 1e-12);
+        close_l2(&inv(&a, false, Some(0.)), &inv(&a, true, None), 1e-12);
+    }
+
+    #[test]
+    fn test_rolling_least_squares_deltas() {
+        let (targets, features) = make_data(None);
+        let absolute = solve_rolling_ols(
+            &targets,
+            &features,
+            100usize,
+            Some(10usize),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let deltas = solve_rolling_ols(
+            &targets,
+            &features,
+            100usize,
+            Some(10usize),
+            None,
+            None,
+            None,
+            Some(true),
+            None,
+        );
+        assert!(deltas.slice(s![..9, ..]).iter().all(|d| d.is_nan()));
+        assert!(deltas.row(9).iter().all(|d| *d == 0.));
+        let expected = &absolute.slice(s![9.., ..]) - &absolute.row(9);
+        close_l2(&deltas.slice(s![9.., ..]), &expected, 1e-12);
+    }
+
+    #[test]
+    fn test_blocked_cv() {
+        let folds = blocked_cv_folds(100, 4, 3);
+        assert_eq!(folds.len(), 4);
+        let (train, validation) = &folds[1];
+        assert_eq!(validation, &(25..50).collect::<Vec<usize>>());
+        // 3 observations purged on each side of the validation block
+        assert_eq!(train.len(), 100 - 25 - 6);
+        assert!(train.iter().all(|i| *i < 22 || *i >= 53));
+        // edge blocks are only purged on one side
+        assert_eq!(folds[0].0.first(), Some(&28));
+        assert_eq!(folds[3].0.last(), Some(&71));
+
+        let x = Array2::<f64>::random((200, 3), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1.0, -1.0, 0.5])
+            + Array1::<f64>::random(200, Normal::new(0., 0.1).unwrap());
+        let score = blocked_cv_score(&y, &x, 5, 0., Some(5));
+        assert!(score > 0. && score < 0.02);
+        assert!(blocked_cv_score(&y, &x, 5, 1e4, None) > score);
+    }
+
+    #[test]
+    fn test_residual_acf() {
+        let white_noise = Array1::<f64>::random(20_000, Normal::new(0., 1.).unwrap());
+        let acf = residual_acf(&white_noise, 10);
+        assert_eq!(acf.len(), 10);
+        // within ~4 standard errors of zero
+        assert!(acf.iter().all(|r| r.abs() < 4. / 20_000f64.sqrt()));
+
+        let phi = 0.7;
+        let mut ar1 = white_noise.clone();
+        for t in 1..ar1.len() {
+            ar1[t] += phi * ar1[t - 1];
+        }
+        let acf = residual_acf(&ar1, 4);
+        for (k, r) in acf.iter().enumerate() {
+            assert!((r - phi.powi(k as i32 + 1)).abs() < 0.06);
+        }
+    }
+
+    #[test]
+    fn test_compute_qr() {
+        let mut x = Array2::<f64>::random((50, 5), Normal::new(0., 1.).unwrap());
+        // scale the columns differently so that pivoting reorders them
+        for (j, mut column) in x.axis_iter_mut(Axis(1)).enumerate() {
+            column *= (j + 1) as f64;
+        }
+        let qr = compute_qr(&x);
+        assert_eq!(qr.q.shape(), &[50, 5]);
+        assert_eq!(qr.r.shape(), &[5, 5]);
+        assert!((0..5).all(|i| (0..i).all(|j| qr.r[[i, j]] == 0.)));
+        close_l2(&qr.q.t().dot(&qr.q), &Array2::<f64>::eye(5), 1e-10);
+        close_l2(&qr.q.dot(&qr.r), &x.select(Axis(1), &qr.permutation), 1e-10);
+        // reconstruct X = Q R P^T
+        let mut reconstructed = Array2::<f64>::zeros((50, 5));
+        for (j, &p) in qr.permutation.iter().enumerate() {
+            reconstructed
+                .column_mut(p)
+                .assign(&qr.q.dot(&qr.r.column(j)));
+        }
+        close_l2(&reconstructed, &x, 1e-10);
+    }
+
+    #[test]
+    fn test_ols_standard_errors_qr() {
+        // ill-conditioned design with a known SVD X = U S V^T (condition number 1e7)
+        let u = compute_qr(&Array2::<f64>::random(
+            (200, 4),
+            Normal::new(0., 1.).unwrap(),
+        ))
+        .q;
+        let v = compute_qr(&Array2::<f64>::random((4, 4), Normal::new(0., 1.).unwrap())).q;
+        let s = array![1e3, 1e1, 1e-1, 1e-4];
+        let x = (&u * &s).dot(&v.t());
+        let residuals = Array1::<f64>::random(200, Normal::new(0., 1.).unwrap());
+
+        // reference: inv(X^T X) = V S^-2 V^T from the known factors
+        let sigma2 = residuals.dot(&residuals) / 196.;
+        let reference = (&v * &v)
+            .dot(&s.mapv(|s| 1. / (s * s)))
+            .mapv(|d| (d * sigma2).sqrt());
+
+        let relative_error = |se: &Array1<f64>| {
+            ((se - &reference) / &reference)
+                .iter()
+                .fold(0., |acc: f64, e| acc.max(e.abs()))
+        };
+        let qr_error = relative_error(&ols_standard_errors(&x, &residuals, Some(true)));
+        let normal_error = relative_error(&ols_standard_errors(&x, &residuals, None));
+        assert!(qr_error < 1e-6);
+        assert!(qr_error < normal_error);
+    }
+
+    #[test]
+    fn test_elastic_net_path() {
+        let x = Array2::<f64>::random((300, 10), Normal::new(0., 1.).unwrap());
+        let mut beta = Array1::<f64>::zeros(10);
+        beta[0] = 2.0;
+        beta[3] = -1.5;
+        beta[7] = 1.0;
+        let y = x.dot(&beta) + Array1::<f64>::random(300, Normal::new(0., 0.5).unwrap());
+        let alphas = Array1::logspace(10., 0.5, -4., 30);
+
+        let path =
+            solve_elastic_net_path(&y, &x, &alphas, Some(1.0), None, Some(1e-10), None, None);
+        assert_eq!(path.shape(), &[30, 10]);
+        // warm-started path matches independent fits
+        for i in [0usize, 10, 29] {
+            let expected = solve_elastic_net(
+                &y,
+                &x,
+                alphas[i],
+                Some(1.0),
+                None,
+                Some(1e-10),
+                None,
+                None,
+                None,
+                None,
+            );
+            close_l2(&path.row(i), &expected, 1e-6);
+        }
+
+        let truncated = solve_elastic_net_path(
+            &y,
+            &x,
+            &alphas,
+            Some(1.0),
+            None,
+            Some(1e-10),
+            None,
+            Some(1e-4),
+        );
+        assert!(truncated.shape()[0] < 30);
+        close_l2(
+            &truncated,
+            &path.slice(s![..truncated.shape()[0], ..]),
+            1e-12,
+        );
+        // the signal features have entered before exiting early
+        let last = truncated.row(truncated.shape()[0] - 1);
+        assert!([0, 3, 7].iter().all(|j| last[*j].abs() > 0.5));
+    }
+
+    #[test]
+    fn test_quantile_regression() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (500, 1),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let y = x.dot(&array![1.0, 2.0]) + Array1::<f64>::random(500, Normal::new(0., 1.).unwrap());
+        let median = solve_quantile(&y, &x, 0.5, None, None, None);
+        close_l2(&median, &array![1.0, 2.0], 0.25);
+        let upper = solve_quantile(&y, &x, 0.9, None, None, None);
+        // the 90% quantile of the N(0, 1) noise is ~1.28
+        assert!((upper[0] - median[0] - 1.28).abs() < 0.3);
+
+        // uniform weights reproduce the unweighted fit
+        let uniform = Array1::<f64>::from_elem(500, 4.);
+        close_l2(
+            &solve_quantile(&y, &x, 0.9, Some(&uniform), None, None),
+            &upper,
+            1e-6,
+        );
+
+        // an intercept only fit is the (weighted) sample quantile: heavily weighting the
+        // observations with large targets pulls the quantile toward them
+        let ones = Array2::<f64>::ones((500, 1));
+        let unweighted = solve_quantile(&y, &ones, 0.5, None, None, None)[0];
+        let weights = y.mapv(|y_i| if y_i > 3. { 20. } else { 1. });
+        let weighted = solve_quantile(&y, &ones, 0.5, Some(&weights), None, None)[0];
+        assert!(weighted > unweighted + 0.5);
+    }
+
+    #[test]
+    fn test_degrees_of_freedom() {
+        let x = Array2::<f64>::random((100, 5), Normal::new(0., 1.).unwrap());
+        assert_eq!(degrees_of_freedom(FitType::OLS, 100, 5, None, None), 95.);
+        assert_eq!(degrees_of_freedom(FitType::GLM, 100, 5, None, None), 95.);
+        let ridge = degrees_of_freedom(FitType::Ridge, 100, 5, Some(&x), Some(10.));
+        assert!((ridge - (100. - ridge_effective_df(&x, 10.))).abs() < 1e-8);
+        assert!(ridge > 95. && ridge < 100.);
+        // ridge without a penalty is OLS
+        let unpenalized = degrees_of_freedom(FitType::Ridge, 100, 5, Some(&x), None);
+        assert!((unpenalized - 95.).abs() < 1e-8);
+        let lasso = degrees_of_freedom(FitType::Lasso { n_active: 2 }, 100, 5, None, None);
+        assert_eq!(lasso, 98.);
+    }
+
+    #[test]
+    fn test_jackknife_se() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (500, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        // heteroskedastic noise
+        let noise =
+            Array1::<f64>::random(500, Normal::new(0., 1.).unwrap()) * &x.column(1).mapv(f64::abs);
+        let y = x.dot(&array![1.0, 0.5, -0.5]) + &noise;
+        let coefficients = solve_ols(&y, &x, None, None);
+        let jackknife = jackknife_se(&y, &x, &coefficients);
+
+        // matches explicit leave-one-out refits
+        let mut deltas = Array2::<f64>::zeros((500, 3));
+        for i in 0..500 {
+            let keep: Vec<usize> = (0..500).filter(|k| *k != i).collect();
+            let refit = solve_ols(
+                &y.select(Axis(0), &keep),
+                &x.select(Axis(0), &keep),
+                None,
+                None,
+            );
+            deltas.row_mut(i).assign(&refit);
+        }
+        let mean = deltas.mean_axis(Axis(0)).unwrap();
+        let explicit = (&deltas - &mean)
+            .mapv(|d| d * d)
+            .sum_axis(Axis(0))
+            .mapv(|v| (v * 499. / 500.).sqrt());
+        close_l2(&jackknife, &explicit, 1e-8);
+
+        let residuals = &y - &x.dot(&coefficients);
+        let hc3 = ols_robust_covariance(&x, &residuals, CovarianceType::HC3)
+            .diag()
+            .mapv(f64::sqrt);
+        assert!(((&jackknife - &hc3) / &hc3).iter().all(|e| e.abs() < 0.05));
+    }
+
+    #[test]
+    fn test_ridge_dual() {
+        let x = Array2::<f64>::random((30, 500), Normal::new(0., 1.).unwrap());
+        let y = Array1::<f64>::random(30, Normal::new(0., 1.).unwrap());
+        for alpha in [0.1, 1.0, 50.0] {
+            let dual = solve_ridge_dual(&y, &x, alpha);
+            let primal = solve_ridge(&y, &x, alpha, Some(SolveMethod::SVD), None);
+            close_l2(&dual, &primal, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_flag_outliers() {
+        use ndarray_rand::rand::rngs::StdRng;
+        use ndarray_rand::rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut x = add_intercept(&Array2::<f64>::random_using(
+            (200, 1),
+            Normal::new(0., 1.).unwrap(),
+            &mut rng,
+        ));
+        let mut y = x.dot(&array![0.5, 1.0])
+            + Array1::<f64>::random_using(200, Normal::new(0., 0.1).unwrap(), &mut rng);
+        // influential points: high leverage and far from the regression line
+        for i in [5usize, 50, 150] {
+            x[[i, 1]] = 6.;
+            y[i] = -3.;
+        }
+        let default = flag_outliers(&y, &x, None);
+        assert!(default[5] && default[50] && default[150]);
+        let strict = flag_outliers(&y, &x, Some(0.6));
+        let flagged: Vec<usize> = (0..200).filter(|i| strict[*i]).collect();
+        assert_eq!(flagged, vec![5, 50, 150]);
+
+        let coefficients = solve_ols(&y, &x, None, None);
+        let d = dffits(&y, &x, &coefficients);
+        assert!(d[5] < -1.);
+    }
+
+    #[test]
+    fn test_dfbetas() {
+        use ndarray_rand::rand::rngs::StdRng;
+        use ndarray_rand::rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut x = add_intercept(&Array2::<f64>::random_using(
+            (100, 2),
+            Normal::new(0., 1.).unwrap(),
+            &mut rng,
+        ));
+        let mut y = x.dot(&array![0.5, 1.0, -1.0])
+            + Array1::<f64>::random_using(100, Normal::new(0., 0.1).unwrap(), &mut rng);
+        // observation 7 pulls the slope of the first feature only
+        x[[7, 1]] = 5.;
+        x[[7, 2]] = 0.;
+        y[7] = 0.5 + 5. * 2.;
+        let coefficients = solve_ols(&y, &x, None, None);
+        let influence = dfbetas(&y, &x, &coefficients);
+        assert_eq!(influence.shape(), &[100, 3]);
+        let threshold = 2. / 10.;
+        assert!(influence[[7, 1]].abs() > 5. * threshold);
+        assert!(influence[[7, 2]].abs() < influence[[7, 1]].abs() / 5.);
+
+        // compare with an explicit leave-one-out refit
+        let keep: Vec<usize> = (0..100).filter(|i| *i != 7).collect();
+        let (y_loo, x_loo) = (y.select(Axis(0), &keep), x.select(Axis(0), &keep));
+        let refit = solve_ols(&y_loo, &x_loo, None, None);
+        let residuals_loo = &y_loo - &x_loo.dot(&refit);
+        let s_loo = (residuals_loo.dot(&residuals_loo) / (99. - 3.)).sqrt();
+        let xtx_inv = inv(&x.t().dot(&x), true, None);
+        let expected = (&coefficients - &refit) / xtx_inv.diag().mapv(f64::sqrt) / s_loo;
+        close_l2(&influence.row(7), &expected, 1e-8);
+
+        let t = studentized_residuals(&y, &x, &coefficients);
+        assert!(t[7] > 10.);
+    }
+
+    #[test]
+    fn test_predict_sparse() {
+        let x = Array2::<f64>::random((100, 50), Normal::new(0., 1.).unwrap());
+        let mut coefficients = Array1::<f64>::zeros(50);
+        coefficients[3] = 1.5;
+        coefficients[17] = -0.5;
+        coefficients[40] = 1e-14;
+        let dense = x.dot(&coefficients);
+        close_l2(&predict_sparse(&x, &coefficients, None), &dense, 1e-12);
+        close_l2(
+            &predict_sparse(&x, &coefficients, Some(1e-10)),
+            &dense,
+            1e-10,
+        );
+    }
+
+    #[test]
+    fn test_ridge_marginal_likelihood() {
+        // coefficients drawn from the ridge prior with sigma = 1 and alpha = 10
+        let x = Array2::<f64>::random((500, 50), Normal::new(0., 1.).unwrap());
+        let beta = Array1::<f64>::random(50, Normal::new(0., 10f64.powf(-0.5)).unwrap());
+        let y = x.dot(&beta) + Array1::<f64>::random(500, Normal::new(0., 1.).unwrap());
+        let alphas = Array1::logspace(10., -2., 4., 61);
+        let log_ml = ridge_marginal_likelihood(&y, &x, &alphas);
+        let best = log_ml
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+        assert!(alphas[best] > 2. && alphas[best] < 50.);
+
+        // matches the Gaussian log density evaluated directly
+        let alpha = 3.;
+        let c = Array2::<f64>::eye(500) + x.dot(&x.t()) / alpha;
+        let c_inv = inv(&c, true, None);
+        let sigma2 = y.dot(&c_inv.dot(&y)) / 500.;
+        let log_det_c: f64 = gram_eigenvalues(&x).mapv(|e| (1. + e / alpha).ln()).sum();
+        let expected =
+            -0.5 * 500. * ((2. * std::f64::consts::PI * sigma2).ln() + 1.) - 0.5 * log_det_c;
+        let actual = ridge_marginal_likelihood(&y, &x, &array![alpha])[0];
+        assert!((actual - expected).abs() < 1e-6 * expected.abs());
+    }
+
+    #[test]
+    fn test_running_moments() {
+        // large offset to exercise the numerical stability of the updates
+        let x = Array2::<f64>::random((10_000, 3), Normal::new(0., 1.).unwrap()) + 1e6;
+        let mut moments = RunningMoments::new(3);
+        for row in x.rows() {
+            moments.update(&row.to_owned());
+        }
+        assert_eq!(moments.count(), 10_000);
+        let mean = x.mean_axis(Axis(0)).unwrap();
+        close_l2(moments.mean(), &mean, 1e-6);
+        close_l2(&moments.variance(1), &x.var_axis(Axis(0), 1.), 1e-6);
+        let centered = &x - &mean;
+        let covariance = centered.t().dot(&centered) / 10_000.;
+        close_l2(&moments.covariance(0), &covariance, 1e-6);
+        let z = moments.standardize(&x.row(0).to_owned());
+        let expected = (&x.row(0) - &mean) / x.std_axis(Axis(0), 0.);
+        close_l2(&z, &expected, 1e-6);
+    }
+
+    #[test]
+    fn test_elastic_net_with_penalty_matrix() {
+        let x = Array2::<f64>::random((200, 6), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1.0, 1.0, 1.0, 0.0, 0.0, -1.0])
+            + Array1::<f64>::random(200, Normal::new(0., 0.5).unwrap());
+        let identity = Array2::<f64>::eye(6);
+        let generalized = solve_elastic_net_with_penalty_matrix(
+            &y,
+            &x,
+            0.05,
+            Some(0.3),
+            &identity,
+            None,
+            Some(1e-10),
+            None,
+        );
+        let standard = solve_elastic_net(
+            &y,
+            &x,
+            0.05,
+            Some(0.3),
+            None,
+            Some(1e-10),
+            None,
+            None,
+            None,
+            None,
+        );
+        close_l2(&generalized, &standard, 1e-8);
+
+        // a pure (l1_ratio = 0) generalized ridge has the closed form inv(X^T X + n alpha M) X^T y
+        let mut difference = Array2::<f64>::zeros((5, 6));
+        for i in 0..5 {
+            difference[[i, i]] = -1.;
+            difference[[i, i + 1]] = 1.;
+        }
+        let m = difference.t().dot(&difference) + &identity * 0.1;
+        let smooth = solve_elastic_net_with_penalty_matrix(
+            &y,
+            &x,
+            0.5,
+            Some(0.),
+            &m,
+            Some(10_000),
+            Some(1e-12),
+            None,
+        );
+        let expected = inv(&(x.t().dot(&x) + &m * 100.), true, None).dot(&x.t().dot(&y));
+        close_l2(&smooth, &expected, 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be positive semi-definite")]
+    fn test_elastic_net_with_indefinite_penalty_matrix() {
+        let x = Array2::<f64>::random((50, 2), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1.0, -1.0]);
+        // a non-negative diagonal does not make M positive semi-definite: eigenvalues 3 and -1
+        let m = array![[1.0, 2.0], [2.0, 1.0]];
+        solve_elastic_net_with_penalty_matrix(&y, &x, 0.1, Some(0.5), &m, None, None, None);
+    }
+
+    #[test]
+    fn test_ridge_condition_number() {
+        let mut x = Array2::<f64>::random((100, 4), Normal::new(0., 1.).unwrap());
+        x.column_mut(3).mapv_inplace(|v| v * 1e-3);
+        let s = singular_values(&x);
+        let unpenalized = ridge_condition_number(&x, 0.);
+        assert!((unpenalized - (s[0] / s[3]).powi(2)).abs() < 1e-6 * unpenalized);
+        let mut previous = unpenalized;
+        for alpha in [1e-4, 1e-2, 1., 100.] {
+            let condition_number = ridge_condition_number(&x, alpha);
+            assert!(condition_number < previous);
+            previous = condition_number;
+        }
+        assert!(previous > 1.);
+    }
+
+    #[test]
+    fn test_ols_with_stats() {
+        let (targets, features) = make_data(None);
+        let result = solve_ols_with_stats(&targets, &features, None, None);
+        close_l2(
+            &result.coef,
+            &solve_ols(&targets, &features, None, None),
+            1e-12,
+        );
+        close_l2(
+            &result.residuals,
+            &(&targets - &features.dot(&result.coef)),
+            1e-12,
+        );
+        assert!((result.rss - result.residuals.dot(&result.residuals)).abs() < 1e-8);
+        let fitted = features.dot(&result.coef);
+        assert!((result.r_squared - r_squared(&targets, &fitted, None, None)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ols_coef_covariance() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (300, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let y = x.dot(&array![1.0, 0.5, -0.5])
+            + Array1::<f64>::random(300, Normal::new(0., 2.).unwrap());
+        let result = solve_ols_with_stats(&y, &x, None, None);
+        let covariance = ols_coef_covariance(&x, &result.residuals, 297);
+        close_l2(&covariance, &covariance.t(), 1e-14);
+        let expected = inv(&x.t().dot(&x), true, None) * (result.rss / 297.);
+        close_l2(&covariance, &expected, 1e-12);
+        close_l2(
+            &covariance.diag().mapv(f64::sqrt),
+            &ols_standard_errors(&x, &result.residuals, None),
+            1e-12,
+        );
+        // homoskedastic data: classical and HC0 covariances roughly agree
+        let hc0 = ols_robust_covariance(&x, &result.residuals, CovarianceType::HC0);
+        assert!(((&hc0.diag() - &covariance.diag()) / covariance.diag())
+            .iter()
+            .all(|e| e.abs() < 0.3));
+    }
+
+    #[test]
+    fn test_chow_test() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (400, 1),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let noise = Array1::<f64>::random(400, Normal::new(0., 1.).unwrap());
+        let stable = x.dot(&array![0.5, 1.0]) + &noise;
+        let (f_stable, p_stable) = chow_test(&stable, &x, 200);
+        assert!(f_stable >= 0.);
+        assert!(p_stable > 0.001);
+
+        let mut broken = stable.clone();
+        for i in 200..400 {
+            broken[i] += x[[i, 1]] * 1.0;
+        }
+        let (f_broken, p_broken) = chow_test(&broken, &x, 200);
+        assert!(f_broken > f_stable);
+        assert!(p_broken < 1e-6);
+    }
+
+    #[test]
+    fn test_cusum_residuals() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (400, 1),
+            Normal::new(1., 1.).unwrap(),
+        ));
+        let noise = Array1::<f64>::random(400, Normal::new(0., 0.5).unwrap());
+        let stable = x.dot(&array![0.5, 1.0]) + &noise;
+        let (cusum, bound, _) = cusum_residuals(&stable, &x, 20);
+        assert!(cusum.slice(s![..20]).iter().all(|c| c.is_nan()));
+        assert!(bound[399] > bound[20]);
+        let stable_ratio = (&cusum / &bound)
+            .slice(s![20..])
+            .fold(0., |acc: f64, r| acc.max(r.abs()));
+
+        // level shift of the intercept half way through
+        let mut shifted = stable.clone();
+        shifted.slice_mut(s![200..]).mapv_inplace(|y| y + 1.5);
+        let (cusum, bound, breach) = cusum_residuals(&shifted, &x, 20);
+        assert!(breach.is_some());
+        let ratio = (&cusum / &bound).slice(s![20..]).mapv(f64::abs);
+        let (t_max, max_ratio) =
+            ratio.iter().enumerate().fold(
+                (0, 0.),
+                |acc, (t, r)| if *r > acc.1 { (t + 20, *r) } else { acc },
+            );
+        assert!(t_max > 200);
+        assert!(max_ratio > 1. && max_ratio > stable_ratio);
+    }
+
+    #[test]
+    fn test_ols_t_statistics_and_p_values() {
+        let t = ols_t_statistics(&array![2.0, -1.0, 0.0, 1.0], &array![1.0, 0.5, 0.3, 0.0]);
+        assert_eq!(t.slice(s![..3]), array![2.0, -2.0, 0.0]);
+        assert!(t[3].is_infinite());
+
+        let p = ols_p_values(&t, 10);
+        // two-sided p-value of |t| = 2 with 10 degrees of freedom
+        assert!((p[0] - 0.07338803477074).abs() < 1e-8);
+        assert!((p[0] - p[1]).abs() < 1e-14);
+        assert!((p[2] - 1.).abs() < 1e-14);
+        assert_eq!(p[3], 0.);
+        // approaches the normal distribution for large dof
+        assert!((ols_p_values(&array![1.959963984540054], 100_000)[0] - 0.05).abs() < 1e-4);
+        assert!(ols_p_values(&t, 0).iter().all(|p| p.is_nan()));
+    }
+
+    #[test]
+    fn test_ols_confidence_intervals() {
+        let coef = array![1.0, -2.0];
+        let std_errors = array![0.5, 0.1];
+        let (lower, upper) = ols_confidence_intervals(&coef, &std_errors, 10, 0.95);
+        // t_{0.975, 10} = 2.228138851986
+        close_l2(
+            &lower,
+            &array![1.0 - 0.5 * 2.228138851986, -2.0 - 0.1 * 2.228138851986],
+            1e-8,
+        );
+        close_l2(&((&lower + &upper) / 2.), &coef, 1e-12);
+        // the bounds have the chosen two-sided p-value
+        let t = ols_t_statistics(&(&coef - &lower), &std_errors);
+        close_l2(&ols_p_values(&t, 10), &array![0.05, 0.05], 1e-8);
+        let (lower_90, _) = ols_confidence_intervals(&coef, &std_errors, 10, 0.9);
+        assert!(lower_90
+            .iter()
+            .zip(lower.iter())
+            .all(|(l90, l95)| l90 > l95));
+    }
+
+    #[test]
+    fn test_recursive_residuals() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (2_000, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let y = x.dot(&array![0.5, 1.0, -1.0])
+            + Array1::<f64>::random(2_000, Normal::new(0., 1.).unwrap());
+        let residuals = recursive_residuals(&y, &x);
+        assert_eq!(residuals.len(), 2_000 - 3);
+        assert!((residuals.var(1.) - 1.).abs() < 0.15);
+        assert!(residuals.mean().unwrap().abs() < 0.1);
+
+        // matches refitting on the expanding window
+        for t in [3usize, 10, 1_999] {
+            let x_past = x.slice(s![..t, ..]).to_owned();
+            let xtx_inv = inv(&x_past.t().dot(&x_past), true, None);
+            let coefficients = xtx_inv.dot(&x_past.t().dot(&y.slice(s![..t])));
+            let x_t = x.row(t);
+            let expected =
+                (y[t] - x_t.dot(&coefficients)) / (1. + x_t.dot(&xtx_inv.dot(&x_t))).sqrt();
+            assert!((residuals[t - 3] - expected).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_boxcox() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (500, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let log_y = x.dot(&array![1.0, 0.3, -0.2])
+            + Array1::<f64>::random(500, Normal::new(0., 0.2).unwrap());
+        let y = log_y.mapv(f64::exp);
+
+        // lambda = 1 only shifts the targets by -1, i.e. the intercept
+        let (coefficients, lambda) = solve_boxcox(&y, &x, Some(1.));
+        assert_eq!(lambda, 1.);
+        let ols = solve_ols(&y, &x, None, None);
+        close_l2(&coefficients, &(&ols - &array![1.0, 0.0, 0.0]), 1e-8);
+
+        // log-normal targets select lambda near 0
+        let (coefficients, lambda) = solve_boxcox(&y, &x, None);
+        assert!(lambda.abs() <= 0.2);
+        assert!(coefficients[1] > 0. && coefficients[2] < 0.);
+        let (log_fit, _) = solve_boxcox(&y, &x, Some(0.));
+        close_l2(&log_fit, &solve_ols(&log_y, &x, None, None), 1e-8);
+    }
+
+    #[test]
+    fn test_fit_intercept() {
+        let x = Array2::<f64>::random((400, 3), Normal::new(2., 1.).unwrap());
+        let y = x.dot(&array![0.5, -1.0, 0.25])
+            + 5.0
+            + Array1::<f64>::random(400, Normal::new(0., 0.1).unwrap());
+
+        // without a penalty, the intercept fit matches OLS on the augmented design
+        let ols = solve_ols(&y, &add_intercept(&x), None, None);
+        let ridge = solve_ridge_with_intercept(&y, &x, 0.0, None, None);
+        assert_eq!(ridge.len(), 4);
+        close_l2(&ridge, &ols, 1e-6);
+
+        // a heavy penalty shrinks the slopes but the intercept tracks mean(y) - mean(x)^T B
+        let ridge = solve_ridge_with_intercept(&y, &x, 1e6, None, None);
+        assert!(ridge.slice(s![1..]).iter().all(|b| b.abs() < 0.01));
+        assert!((ridge[0] - y.mean().unwrap()).abs() < 0.1);
+
+        let enet = solve_elastic_net_with_intercept(
+            &y,
+            &x,
+            0.001,
+            Some(0.5),
+            None,
+            Some(1e-10),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(enet.len(), 4);
+        close_l2(&enet, &ols, 0.05);
+    }
+
+    #[test]
+    fn test_elastic_net_standardize() {
+        let x = Array2::<f64>::random((500, 3), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![0.8, -0.4, 0.1])
+            + Array1::<f64>::random(500, Normal::new(0., 0.1).unwrap());
+        let fit = |x: &Array2<f64>| {
+            solve_elastic_net(
+                &y,
+                x,
+                0.05,
+                Some(0.5),
+                None,
+                Some(1e-10),
+                None,
+                None,
+                None,
+                Some(true),
+            )
+        };
+
+        // rescaling a column only rescales its coefficient when features are standardized
+        let scale = array![1000.0, 1.0, 0.001];
+        close_l2(&fit(&(&x * &scale)), &(fit(&x) / &scale), 1e-6);
+
+        // a constant column is left unscaled (its coefficient matches the unstandardized fit)
+        let x_bias = add_intercept(&x);
+        let standardized = fit(&x_bias);
+        let plain = solve_elastic_net(
+            &y,
+            &x_bias,
+            0.05,
+            Some(0.5),
+            None,
+            Some(1e-10),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(standardized.iter().all(|b| b.is_finite()));
+        assert!((standardized[0] - plain[0]).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_reset_test() {
+        use ndarray_rand::rand::rngs::StdRng;
+        use ndarray_rand::rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let x = add_intercept(&Array2::<f64>::random_using(
+            (500, 2),
+            Normal::new(0., 1.).unwrap(),
+            &mut rng,
+        ));
+        let noise = Array1::<f64>::random_using(500, Normal::new(0., 0.5).unwrap(), &mut rng);
+
+        // genuinely linear data: the powers of the fitted values add nothing
+        let y = x.dot(&array![1.0, 2.0, -1.0]) + &noise;
+        let coefficients = solve_ols(&y, &x, None, None);
+        let (f_statistic, p_value) = reset_test(&y, &x, &coefficients, &[2, 3]);
+        assert!(f_statistic >= 0. && p_value > 0.001);
+
+        // an omitted quadratic term is detected
+        let y_quadratic = &y + &x.column(1).mapv(|v| 0.5 * v * v);
+        let coefficients = solve_ols(&y_quadratic, &x, None, None);
+        let (f_statistic, p_value) = reset_test(&y_quadratic, &x, &coefficients, &[2, 3]);
+        assert!(f_statistic > 20. && p_value < 1e-6);
+    }
+
+    #[test]
+    fn test_ard_ridge() {
+        let x = Array2::<f64>::random((300, 6), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![2.0, -1.5, 1.0, 0.0, 0.0, 0.0])
+            + Array1::<f64>::random(300, Normal::new(0., 0.5).unwrap());
+        let (coefficients, lambdas) = solve_ard_ridge(&y, &x, None, None);
+        let ols = solve_ols(&y, &x, None, None);
+        // signal features keep (nearly) their OLS estimates and small precisions
+        close_l2(&coefficients.slice(s![..3]), &ols.slice(s![..3]), 0.05);
+        assert!(lambdas.slice(s![..3]).iter().all(|l| *l < 10.));
+        // noise features are pruned by large learned precisions
+        assert!(lambdas.slice(s![3..]).iter().all(|l| *l > 100.));
+        for j in 3..6 {
+            assert!(coefficients[j].abs() < 0.1 && coefficients[j].abs() <= ols[j].abs());
+        }
+    }
+
+    #[test]
+    fn test_generic_f32_solvers() {
+        let x = Array2::<f64>::random((500, 4), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1.0, -0.5, 0.25, 2.0])
+            + Array1::<f64>::random(500, Normal::new(0., 0.1).unwrap());
+        let (x_32, y_32) = (x.mapv(|v| v as f32), y.mapv(|v| v as f32));
+        let to_f64 = |b: Array1<f32>| b.mapv(|v| v as f64);
+
+        for solve_method in [SolveMethod::QR, SolveMethod::SVD] {
+            let expected = solve_ols(&y, &x, None, None);
+            let coefficients = solve_ols_generic(&y_32, &x_32, Some(solve_method), None);
+            close_l2(&to_f64(coefficients), &expected, 1e-4);
+        }
+        for solve_method in [SolveMethod::Cholesky, SolveMethod::LU, SolveMethod::SVD] {
+            let expected = solve_ridge(&y, &x, 10.0, None, None);
+            let coefficients = solve_ridge_generic(&y_32, &x_32, 10.0, Some(solve_method), None);
+            close_l2(&to_f64(coefficients), &expected, 1e-4);
+        }
+        let expected = solve_elastic_net(
+            &y,
+            &x,
+            0.01,
+            Some(0.5),
+            None,
+            Some(1e-6),
+            None,
+            None,
+            None,
+            None,
+        );
+        let coefficients = solve_elastic_net_generic(
+            &y_32,
+            &x_32,
+            0.01,
+            Some(0.5),
+            None,
+            Some(1e-6),
+            None,
+            None,
+            None,
+            None,
+        );
+        close_l2(&to_f64(coefficients), &expected, 1e-3);
+    }
+
+    #[test]
+    fn test_partial_residuals() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (200, 3),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let y = x.dot(&array![0.5, 1.0, -2.0, 0.3])
+            + Array1::<f64>::random(200, Normal::new(0., 0.2).unwrap());
+        let coefficients = solve_ols(&y, &x, None, None);
+        for j in 0..4 {
+            let partial = partial_residuals(&y, &x, &coefficients, j);
+            // adding back the other components reconstructs the targets
+            let mut others = coefficients.clone();
+            others[j] = 0.;
+            close_l2(&(&partial + &x.dot(&others)), &y, 1e-10);
+        }
+        // the partial residuals of a feature regress on it with its own coefficient
+        let partial = partial_residuals(&y, &x, &coefficients, 2);
+        let slope = solve_ols(&partial, &x.select(Axis(1), &[0, 2]), None, None)[1];
+        assert!((slope - coefficients[2]).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_elastic_net_path_weighted() {
+        let x = Array2::<f64>::random((300, 5), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1.0, 0.0, -0.5, 0.0, 0.25])
+            + Array1::<f64>::random(300, Normal::new(0., 0.3).unwrap());
+        let weights = Array1::from_iter((0..300).map(|i| 0.99_f64.powi(300 - i)));
+        let alphas = array![0.001, 0.01, 0.1, 0.5];
+        let path = solve_elastic_net_path_weighted(
+            &y,
+            &x,
+            &weights,
+            &alphas,
+            Some(0.7),
+            Some(10_000),
+            Some(1e-12),
+            None,
+        );
+        assert_eq!(path.shape(), &[4, 5]);
+
+        // each alpha matches an independent fit on sqrt(w)-scaled data (weights of mean one)
+        let sqrt_w = (&weights / weights.mean().unwrap()).mapv(f64::sqrt);
+        let (y_w, x_w) = (&y * &sqrt_w, &x * &sqrt_w.view().insert_axis(Axis(1)));
+        for (i, alpha) in [0.5, 0.1, 0.01, 0.001].iter().enumerate() {
+            let expected = solve_elastic_net(
+                &y_w,
+                &x_w,
+                *alpha,
+                Some(0.7),
+                Some(10_000),
+                Some(1e-12),
+                None,
+                None,
+                None,
+                None,
+            );
+            close_l2(&path.row(i), &expected, 1e-8);
+        }
+
+        // an empty grid starts from the weighted alpha_max, at which all coefficients are zero
+        let path = solve_elastic_net_path_weighted(
+            &y,
+            &x,
+            &weights,
+            &Array1::zeros(0),
+            Some(0.7),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(path.shape(), &[100, 5]);
+        assert!(path.row(0).iter().all(|w| w.abs() < 1e-10));
+        assert!(path.row(1).iter().any(|w| *w != 0.));
+        close_l2(
+            &path,
+            &solve_elastic_net_path(
+                &y_w,
+                &x_w,
+                &Array1::zeros(0),
+                Some(0.7),
+                None,
+                None,
+                None,
+                None,
+            ),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn test_huber() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (500, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let beta = array![1.0, 2.0, -1.0];
+        let clean = x.dot(&beta) + Array1::<f64>::random(500, Normal::new(0., 0.5).unwrap());
+
+        // without outliers, Huber is close to OLS
+        let huber = solve_huber(&clean, &x, 1.345, None, None, None);
+        close_l2(&huber, &solve_ols(&clean, &x, None, None), 0.05);
+
+        // gross outliers distort OLS but barely move the Huber fit
+        let mut y = clean.clone();
+        for i in (0..500).step_by(20) {
+            y[i] += 50.;
+        }
+        let ols_error = (&solve_ols(&y, &x, None, None) - &beta)
+            .mapv(f64::abs)
+            .sum();
+        let huber_error = (&solve_huber(&y, &x, 1.345, None, None, None) - &beta)
+            .mapv(f64::abs)
+            .sum();
+        assert!(huber_error < 0.2);
+        assert!(huber_error < 0.2 * ols_error);
+    }
+
+    #[test]
+    fn test_huber_weights() {
+        use ndarray_rand::rand::rngs::StdRng;
+        use ndarray_rand::rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let x = add_intercept(&Array2::<f64>::random_using(
+            (300, 2),
+            Normal::new(0., 1.).unwrap(),
+            &mut rng,
+        ));
+        let noise = Array1::<f64>::random_using(300, Normal::new(0., 0.5).unwrap(), &mut rng);
+        let mut y = x.dot(&array![1.0, 2.0, -1.0]) + noise;
+        let outliers = [5, 50, 150, 250];
+        for i in outliers {
+            y[i] += 30.;
+        }
+        let (coefficients, weights) = solve_huber_with_weights(&y, &x, 1.345, None, None, None);
+        close_l2(
+            &coefficients,
+            &solve_huber(&y, &x, 1.345, None, None, None),
+            1e-12,
+        );
+        assert!(weights.iter().all(|w| *w > 0. && *w <= 1.));
+        // 30 / 0.5 = 60 robust standard deviations: weights around 1.345 / 60
+        assert!(outliers.iter().all(|i| weights[*i] < 0.05));
+        let clean: Vec<f64> = (0..300)
+            .filter(|i| !outliers.contains(i))
+            .map(|i| weights[i])
+            .collect();
+        // within delta = 1.345 robust standard deviations, i.e. ~82% of normal residuals
+        assert!(clean.iter().filter(|w| **w == 1.).count() > 200);
+        assert!(clean.iter().all(|w| *w > 0.2));
+    }
+
+    #[test]
+    fn test_huber_fixed_scale() {
+        use ndarray_rand::rand::rngs::StdRng;
+        use ndarray_rand::rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let x = add_intercept(&Array2::<f64>::random_using(
+            (40, 1),
+            Normal::new(0., 1.).unwrap(),
+            &mut rng,
+        ));
+        let mut y = x.dot(&array![0.5, 1.0])
+            + Array1::<f64>::random_using(40, Normal::new(0., 4.).unwrap(), &mut rng);
+        y[3] += 40.;
+        let (coefficients, weights) =
+            solve_huber_with_weights(&y, &x, 1.345, Some(1000), Some(1e-14), Some(1.));
+        // the fixed-scale Huber estimating equations sum_i psi(r_i / s) x_i = 0 hold
+        let residuals = &y - &x.dot(&coefficients);
+        let psi = residuals.mapv(|r| r.clamp(-1.345, 1.345));
+        assert!(x.t().dot(&psi).iter().all(|g| g.abs() < 1e-5));
+        assert!(weights[3] < 0.1);
+
+        // pulling all residuals beyond 2 back to just past the threshold shrinks their MAD, but
+        // leaves every psi(r_i / s), and hence the fixed-scale fit, unchanged
+        let moved = Array1::from_iter(residuals.iter().map(|r| {
+            if r.abs() > 2. {
+                1.5 * r.signum()
+            } else {
+                *r
+            }
+        }));
+        let y_moved = x.dot(&coefficients) + &moved;
+        let fixed = solve_huber(&y_moved, &x, 1.345, Some(1000), Some(1e-14), Some(1.));
+        close_l2(&fixed, &coefficients, 1e-6);
+        // whereas a fit re-estimating the MAD does move
+        let mad_fit = solve_huber(&y, &x, 1.345, None, None, None);
+        let mad_fit_moved = solve_huber(&y_moved, &x, 1.345, None, None, None);
+        assert!((&mad_fit - &mad_fit_moved).mapv(f64::abs).sum() > 1e-3);
+
+        // a huge fixed scale downweights nothing: OLS, whatever the MAD of the residuals
+        let (coefficients, weights) =
+            solve_huber_with_weights(&y, &x, 1.345, None, None, Some(1e6));
+        close_l2(&coefficients, &solve_ols(&y, &x, None, None), 1e-10);
+        assert!(weights.iter().all(|w| *w == 1.));
+    }
+
+    #[test]
+    fn test_huber_convergence_large_noise() {
+        use ndarray_rand::rand::rngs::StdRng;
+        use ndarray_rand::rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(13);
+        let x = add_intercept(&Array2::<f64>::random_using(
+            (300, 1),
+            Normal::new(0., 1.).unwrap(),
+            &mut rng,
+        ));
+        let mut y = x.dot(&array![5.0, 10.0])
+            + Array1::<f64>::random_using(300, Normal::new(0., 50.).unwrap(), &mut rng);
+        for i in (0..300).step_by(60) {
+            y[i] += 2000.;
+        }
+        // the default tolerance stops (through the relative change of the objective) close to
+        // a fit iterated to a much tighter tolerance, on the scale of the noise
+        let coefficients = solve_huber(&y, &x, 1.345, Some(1000), None, None);
+        let reference = solve_huber(&y, &x, 1.345, Some(1000), Some(1e-14), None);
+        close_l2(&coefficients, &reference, 0.05);
+
+        let truth = array![5.0, 10.0];
+        let huber_error = (&coefficients - &truth).mapv(f64::abs).sum();
+        let ols_error = (&solve_ols(&y, &x, None, None) - &truth)
+            .mapv(f64::abs)
+            .sum();
+        assert!(huber_error < ols_error / 2.);
+    }
+
+    #[test]
+    fn test_trimmed_ols() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (400, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let clean = x.dot(&array![0.5, 1.5, -2.0])
+            + Array1::<f64>::random(400, Normal::new(0., 0.3).unwrap());
+        let clean_fit = solve_ols(&clean, &x, None, None);
+        // no trimming reproduces OLS
+        close_l2(&solve_trimmed_ols(&clean, &x, 0.0), &clean_fit, 1e-12);
+
+        let mut y = clean.clone();
+        for i in (0..400).step_by(25) {
+            y[i] -= 30.;
+        }
+        let ols_distance = (&solve_ols(&y, &x, None, None) - &clean_fit)
+            .mapv(f64::abs)
+            .sum();
+        let trimmed_distance = (&solve_trimmed_ols(&y, &x, 0.1) - &clean_fit)
+            .mapv(f64::abs)
+            .sum();
+        assert!(trimmed_distance < 0.1 * ols_distance);
+    }
+
+    #[test]
+    fn test_tls() {
+        let n = 5_000;
+        let x_true = Array2::<f64>::random((n, 2), Normal::new(0., 1.).unwrap());
+        let beta = array![1.5, -0.75];
+        let noise = Normal::new(0., 0.5).unwrap();
+        // equal noise in the features and the target: OLS is attenuated, TLS is consistent
+        let x = &x_true + &Array2::<f64>::random((n, 2), noise);
+        let y = x_true.dot(&beta) + Array1::<f64>::random(n, noise);
+        let tls = solve_tls(&y, &x);
+        let ols = solve_ols(&y, &x, None, None);
+        let tls_error = (&tls - &beta).mapv(f64::abs).sum();
+        let ols_error = (&ols - &beta).mapv(f64::abs).sum();
+        assert!(tls_error < 0.15);
+        assert!(tls_error < 0.5 * ols_error);
+
+        // noise-free data recovers the coefficients exactly
+        close_l2(&solve_tls(&x_true.dot(&beta), &x_true), &beta, 1e-8);
+
+        // degenerate case: orthogonal columns of equal norm make all singular values equal
+        let x_degenerate = array![[1., 0.], [0., 1.], [0., 0.], [0., 0.]];
+        let y_degenerate = array![0., 0., 1., 0.];
+        let b = solve_tls(&y_degenerate, &x_degenerate);
+        assert!(b.iter().all(|b_j| b_j.is_finite()));
+        close_l2(&b, &array![0., 0.], 1e-10);
+    }
+
+    #[test]
+    fn test_sherman_morrison_update() {
+        let x = Array2::<f64>::random((50, 4), Normal::new(0., 1.).unwrap());
+        let xtx_inv = inv(&x.t().dot(&x), false, None);
+        let u = Array1::<f64>::random(4, Normal::new(0., 1.).unwrap());
+        let v = Array1::<f64>::random(4, Normal::new(0., 1.).unwrap());
+        let updated = sherman_morrison_update(&xtx_inv, &u.view(), &v.view());
+        let expected = woodbury_update(
+            &xtx_inv,
+            &u.clone().insert_axis(Axis(1)),
+            &array![[1.]],
+            &v.clone().insert_axis(Axis(0)),
+            Some(true),
+        );
+        close_l2(&updated, &expected, 1e-10);
+        // matches the direct inverse of the updated matrix, and the rank 1 row update
+        let direct = inv(
+            &(x.t().dot(&x) + outer_product(&u.view(), &v.view())),
+            false,
+            None,
+        );
+        close_l2(&updated, &direct, 1e-8);
+        let row_update = update_xtx_inv(&xtx_inv, &u.clone().insert_axis(Axis(0)), None);
+        close_l2(
+            &sherman_morrison_update(&xtx_inv, &u.view(), &u.view()),
+            &row_update,
+            1e-10,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the rank")]
+    fn test_pcr_rank_deficient() {
+        let x = Array2::<f64>::random((100, 2), Normal::new(0., 1.).unwrap());
+        // third column is a linear combination of the first two: the rank is 2
+        let mut collinear = Array2::<f64>::zeros((100, 3));
+        collinear.slice_mut(s![.., ..2]).assign(&x);
+        collinear
+            .column_mut(2)
+            .assign(&(&x.column(0) - &x.column(1)));
+        let y = x.dot(&array![1.0, -1.0]);
+        // up to the rank is fine
+        assert!(solve_pcr(&y, &collinear, 2, None)
+            .iter()
+            .all(|b| b.is_finite()));
+        solve_pcr(&y, &collinear, 3, None);
+    }
+
+    #[test]
+    fn test_oos_r_squared() {
+        // few samples and many pure noise features: OLS overfits the training set
+        let (n_train, n_features) = (60, 40);
+        let x = add_intercept(&Array2::<f64>::random(
+            (2 * n_train, n_features),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        let y =
+            &x.column(1) * 0.5 + Array1::<f64>::random(2 * n_train, Normal::new(0., 1.).unwrap());
+        let (x_train, x_test) = (
+            x.slice(s![..n_train, ..]).to_owned(),
+            x.slice(s![n_train.., ..]).to_owned(),
+        );
+        let (y_train, y_test) = (
+            y.slice(s![..n_train]).to_owned(),
+            y.slice(s![n_train..]).to_owned(),
+        );
+
+        let in_sample = solve_ols_with_stats(&y_train, &x_train, None, None).r_squared;
+        let oos = oos_r_squared(&y_train, &x_train, &y_test, &x_test, None);
+        assert!(in_sample > 0.4);
+        assert!(oos < 0.2 && oos < in_sample - 0.3);
+
+        // a correctly specified model generalizes
+        let x_small = x.slice(s![.., ..2]).to_owned();
+        let y_clean = x_small.dot(&array![1.0, 2.0])
+            + Array1::<f64>::random(2 * n_train, Normal::new(0., 0.5).unwrap());
+        let oos = oos_r_squared(
+            &y_clean.slice(s![..n_train]).to_owned(),
+            &x_small.slice(s![..n_train, ..]).to_owned(),
+            &y_clean.slice(s![n_train..]).to_owned(),
+            &x_small.slice(s![n_train.., ..]).to_owned(),
+            None,
+        );
+        assert!(oos > 0.8);
+    }
+
+    #[test]
+    fn test_quasibinomial() {
+        use ndarray_rand::rand::thread_rng;
+        use ndarray_rand::rand_distr::{Beta, Binomial, Distribution};
+
+        // beta-binomial proportions out of m trials: overdispersed relative to the binomial
+        let (n, m, precision) = (4_000, 10_u64, 5.0);
+        let x = add_intercept(&Array2::<f64>::random((n, 2), Normal::new(0., 1.).unwrap()));
+        let beta = array![-0.5, 1.0, -0.75];
+        let mut rng = thread_rng();
+        let y = x.dot(&beta).mapv(|eta| {
+            let mu = 1. / (1. + (-eta).exp());
+            let p = Beta::new(mu * precision, (1. - mu) * precision)
+                .unwrap()
+                .sample(&mut rng);
+            Binomial::new(m, p).unwrap().sample(&mut rng) as f64 / m as f64
+        });
+        let (coefficients, dispersion) = solve_quasibinomial(&y, &x, None, None);
+        close_l2(&coefficients, &beta, 0.15);
+        // Var(y) = mu (1 - mu) (1 + (m - 1) / (precision + 1)) / m
+        let expected = (1. + (m as f64 - 1.) / (precision + 1.)) / m as f64;
+        assert!((dispersion / expected - 1.).abs() < 0.15);
+        assert!(dispersion > 1. / m as f64); // beyond binomial variation
+    }
+
+    #[test]
+    fn test_ols_cg() {
+        let x = Array2::<f64>::random((300, 50), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&Array1::<f64>::random(50, Normal::new(0., 1.).unwrap()))
+            + Array1::<f64>::random(300, Normal::new(0., 0.1).unwrap());
+        let expected = solve_ols(&y, &x, Some(SolveMethod::QR), None);
+        close_l2(&solve_ols_cg(&y, &x, None, None), &expected, 1e-6);
+        // dispatched through solve_ols
+        close_l2(
+            &solve_ols(&y, &x, Some(SolveMethod::CG), None),
+            &expected,
+            1e-6,
+        );
+        assert!("cg".parse::<SolveMethod>() == Ok(SolveMethod::CG));
+        // a handful of iterations only gives an approximation
+        let truncated = solve_ols_cg(&y, &x, Some(2), None);
+        assert!((&truncated - &expected).mapv(f64::abs).sum() > 1e-3);
+    }
+
+    #[test]
+    fn test_ols_lsqr() {
+        let x = Array2::<f64>::random((300, 20), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&Array1::<f64>::random(20, Normal::new(0., 1.).unwrap()))
+            + Array1::<f64>::random(300, Normal::new(0., 0.1).unwrap());
+        let expected = solve_ols(&y, &x, Some(SolveMethod::QR), None);
+        close_l2(&solve_ols_lsqr(&y, &x, None, None), &expected, 1e-6);
+        close_l2(
+            &solve_ols(&y, &x, Some(SolveMethod::LSQR), None),
+            &expected,
+            1e-6,
+        );
+        assert!("lsqr".parse::<SolveMethod>() == Ok(SolveMethod::LSQR));
+
+        // ill-conditioned (nearly collinear) columns
+        let mut collinear = x.clone();
+        let perturbed = &x.column(0) + &(&x.column(1) * 1e-4);
+        collinear.column_mut(1).assign(&perturbed);
+        let expected = solve_ols(&y, &collinear, Some(SolveMethod::QR), None);
+        let lsqr = solve_ols_lsqr(&y, &collinear, Some(1_000), Some(1e-14));
+        let fitted = collinear.dot(&expected);
+        close_l2(
+            &collinear.dot(&lsqr),
+            &fitted,
+            1e-6 * fitted.dot(&fitted).sqrt(),
+        );
+    }
+
+    #[test]
+    fn test_tikhonov_effective_df() {
+        let x = Array2::<f64>::random((100, 6), Normal::new(0., 1.).unwrap());
+        for alpha in [0.0, 0.5, 10.0] {
+            let gamma = Array2::<f64>::eye(6) * f64::sqrt(alpha);
+            let df = tikhonov_effective_df(&x, &gamma);
+            assert!((df - ridge_effective_df(&x, alpha)).abs() < 1e-10);
+        }
+        // a first-difference roughness penalty leaves the constant direction unpenalized
+        let mut difference = Array2::<f64>::zeros((5, 6));
+        for i in 0..5 {
+            difference[[i, i]] = -1.;
+            difference[[i, i + 1]] = 1.;
+        }
+        let df = tikhonov_effective_df(&x, &(difference * 1e4));
+        assert!(df > 1. - 1e-3 && df < 1. + 1e-3);
+    }
+
+    #[test]
+    fn test_recursive_least_squares_independent() {
+        let x = Array2::<f64>::random((200, 3), Normal::new(0., 1.).unwrap());
+        let mut y = Array2::<f64>::zeros((200, 2));
+        y.column_mut(0).assign(&x.dot(&array![1.0, -1.0, 0.5]));
+        y.column_mut(1).assign(
+            &(x.dot(&array![0.2, 0.3, 2.0])
+                + Array1::<f64>::random(200, Normal::new(0., 0.1).unwrap())),
+        );
+        // the second target is missing for a while, so its covariance evolves differently
+        for t in 50..80 {
+            y[[t, 1]] = f64::NAN;
+        }
+        let is_valid = vec![true; 200];
+        let coefficients =
+            solve_recursive_least_squares_independent(&y, &x, Some(50.), None, None, &is_valid);
+        assert_eq!(coefficients.shape(), &[200, 2, 3]);
+
+        // each target matches an independent single-target run
+        let first = solve_recursive_least_squares(
+            &y.column(0).to_owned(),
+            &x,
+            Some(50.),
+            None,
+            None,
+            &is_valid,
+        );
+        close_l2(&coefficients.slice(s![.., 0, ..]).to_owned(), &first, 1e-12);
+        let observed: Vec<bool> = (0..200).map(|t| !(50..80).contains(&t)).collect();
+        let second = solve_recursive_least_squares(
+            &y.column(1).mapv(|v| if v.is_finite() { v } else { 0. }),
+            &x,
+            Some(50.),
+            None,
+            None,
+            &observed,
+        );
+        close_l2(
+            &coefficients.slice(s![.., 1, ..]).to_owned(),
+            &second,
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn test_ols_multi() {
+        let x = Array2::<f64>::random((200, 4), Normal::new(0., 1.).unwrap());
+        let beta = Array2::<f64>::random((4, 3), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&beta) + Array2::<f64>::random((200, 3), Normal::new(0., 0.1).unwrap());
+        let methods = [
+            None,
+            Some(SolveMethod::QR),
+            Some(SolveMethod::SVD),
+            Some(SolveMethod::Cholesky),
+            Some(SolveMethod::LU),
+        ];
+        for solve_method in methods {
+            let coefficients = solve_ols_multi(&y, &x, solve_method, None);
+            assert_eq!(coefficients.shape(), &[4, 3]);
+            for j in 0..3 {
+                let expected = solve_ols(&y.column(j).to_owned(), &x, None, None);
+                close_l2(&coefficients.column(j), &expected, 1e-8);
+            }
+        }
+        // wide problems default to the minimum norm (SVD) solution
+        let x_wide = Array2::<f64>::random((5, 8), Normal::new(0., 1.).unwrap());
+        let y_wide = Array2::<f64>::random((5, 2), Normal::new(0., 1.).unwrap());
+        let coefficients = solve_ols_multi(&y_wide, &x_wide, None, None);
+        for j in 0..2 {
+            let expected = solve_ols(
+                &y_wide.column(j).to_owned(),
+                &x_wide,
+                Some(SolveMethod::SVD),
+                None,
+            );
+            close_l2(&coefficients.column(j), &expected, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_glm_profile_ci() {
+        // intercept-only logistic model with 3 successes in 10 trials: the profile deviance has
+        // a closed form, and must exceed its minimum by the 95% chi-squared(1) quantile at both
+        // bounds
+        let x = Array2::<f64>::ones((10, 1));
+        let y = array![1., 1., 1., 0., 0., 0., 0., 0., 0., 0.];
+        let b = (0.3f64 / 0.7).ln();
+        let (lower, upper) = glm_profile_ci(&y, &x, &array![b], None, GlmFamily::Binomial);
+        let deviance = |c: f64| {
+            let p = 1. / (1. + (-c).exp());
+            -2. * (3. * p.ln() + 7. * (1. - p).ln())
+        };
+        assert!(lower[0] < b && b < upper[0]);
+        assert!((deviance(lower[0]) - deviance(b) - 3.841458820694124).abs() < 1e-6);
+        assert!((deviance(upper[0]) - deviance(b) - 3.841458820694124).abs() < 1e-6);
+        // unlike the symmetric Wald interval, it stretches towards the boundary p = 0
+        assert!(b - lower[0] > upper[0] - b);
+
+        // intercept-only poisson model: the MLE is log(mean)
+        let y = array![2., 0., 3., 1., 4., 2., 1., 3.];
+        let x = Array2::<f64>::ones((8, 1));
+        let b = 2f64.ln();
+        let (lower, upper) = glm_profile_ci(&y, &x, &array![b], Some(0.9), GlmFamily::Poisson);
+        let deviance = |c: f64| 2. * (8. * c.exp() - 16. * c);
+        assert!((deviance(lower[0]) - deviance(b) - 2.705543454095404).abs() < 1e-6);
+        assert!((deviance(upper[0]) - deviance(b) - 2.705543454095404).abs() < 1e-6);
+
+        // logistic model with an overlapping slope: a finite interval around the MLE that
+        // roughly agrees with the Wald interval for this moderately sized sample
+        let x = add_intercept(&Array2::from_shape_fn((200, 1), |(i, _)| {
+            (i as f64 * 0.61).sin() * 2.
+        }));
+        let y = Array1::from_shape_fn(200, |i| {
+            let z = 0.5 + x[[i, 1]];
+            if (i as f64 * 0.37).cos() * 2.5 < z {
+                1.
+            } else {
+                0.
+            }
+        });
+        assert!(!detect_separation(&y, &x));
+        let coefficients = {
+            // plain logistic MLE via Newton iterations
+            let mut w = Array1::<f64>::zeros(2);
+            for _ in 0..50 {
+                let p = x.dot(&w).mapv(|z| 1. / (1. + (-z).exp()));
+                let weights = &p * &(1. - &p);
+                let xtwx = x.t().dot(&(&x * &weights.view().insert_axis(Axis(1))));
+                w = &w + &inv(&xtwx, true, None).dot(&x.t().dot(&(&y - &p)));
+            }
+            w
+        };
+        let (lower, upper) = glm_profile_ci(&y, &x, &coefficients, None, GlmFamily::Binomial);
+        for j in 0..2 {
+            assert!(lower[j] < coefficients[j] && coefficients[j] < upper[j]);
+            assert!(upper[j].is_finite() && lower[j].is_finite());
+        }
+
+        // completely separated data: the slope interval is unbounded above
+        let x = add_intercept(&array![[-2.], [-1.], [-0.5], [0.5], [1.], [2.]]);
+        let y = array![0., 0., 0., 1., 1., 1.];
+        let (_, upper) = glm_profile_ci(&y, &x, &array![0., 20.], None, GlmFamily::Binomial);
+        assert!(upper[1].is_infinite());
+    }
+
+    #[test]
+    fn test_sequential_orthogonalize() {
+        let x = Array2::<f64>::random((100, 3), Normal::new(0., 1.).unwrap());
+        // correlate the features so that the attribution depends on the order
+        let x = x.dot(&array![[1., 0.5, 0.2], [0., 1., 0.7], [0., 0., 1.]]);
+        let q = sequential_orthogonalize(&x, &[2, 0, 1]);
+        assert_eq!(q.shape(), &[100, 3]);
+        // the first column in the order is unchanged
+        close_l2(&q.column(0), &x.column(2), 1e-12);
+        let gram = q.t().dot(&q);
+        for i in 0..3 {
+            for j in 0..3 {
+                if i != j {
+                    assert!(gram[[i, j]].abs() < 1e-8 * (gram[[i, i]] * gram[[j, j]]).sqrt());
+                }
+            }
+        }
+        // the columns span the same space: OLS on them gives the same fitted values
+        let y = x.dot(&array![1., -2., 0.5]);
+        let fitted = q.dot(&solve_ols(&y, &q, None, None));
+        close_l2(&fitted, &y, 1e-8);
+
+        // a collinear feature is orthogonalized to a zero column
+        let mut collinear = x.clone();
+        collinear.column_mut(2).assign(&(&x.column(0) * 2.));
+        let q = sequential_orthogonalize(&collinear, &[0, 2]);
+        assert!(q.column(1).iter().all(|v| *v == 0.));
+    }
+
+    #[test]
+    fn test_recursive_least_squares_predictions() {
+        let x = Array2::<f64>::random((50, 2), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1., -0.5]) + Array1::<f64>::random(50, Normal::new(0., 0.1).unwrap());
+        let is_valid = vec![true; 50];
+        let (coefficients, predictions) =
+            solve_recursive_least_squares_with_predictions(&y, &x, None, None, None, &is_valid);
+        close_l2(
+            &coefficients,
+            &solve_recursive_least_squares(&y, &x, None, None, None, &is_valid),
+            1e-12,
+        );
+        // the first prediction uses the (zero) initial state, later ones the previous coefficients
+        assert_eq!(predictions[0], 0.);
+        for t in 1..50 {
+            let expected = x.row(t).dot(&coefficients.row(t - 1));
+            assert!((predictions[t] - expected).abs() < 1e-12);
+            // and differ from the in-sample fitted values after the update
+            assert_ne!(predictions[t], x.row(t).dot(&coefficients.row(t)));
+        }
+    }
+
+    #[test]
+    fn test_solve_gtls() {
+        let x_true = Array2::<f64>::random((200, 2), Normal::new(0., 1.).unwrap());
+        let y = x_true.dot(&array![1.5, -0.7])
+            + Array1::<f64>::random(200, Normal::new(0., 0.1).unwrap());
+        let x = &x_true + &Array2::<f64>::random((200, 2), Normal::new(0., 0.1).unwrap());
+        // uniform error levels (of any magnitude) reduce to ordinary TLS
+        close_l2(
+            &solve_gtls(&y, &x, &array![0.3, 0.3, 0.3]),
+            &solve_tls(&y, &x),
+            1e-8,
+        );
+        // rescaling a feature together with its error level rescales its coefficient
+        let error_std = array![0.1, 0.2, 0.5];
+        let coefficients = solve_gtls(&y, &x, &error_std);
+        let mut x_rescaled = x.clone();
+        x_rescaled.column_mut(0).mapv_inplace(|v| v * 10.);
+        let rescaled = solve_gtls(&y, &x_rescaled, &array![1., 0.2, 0.5]);
+        close_l2(
+            &rescaled,
+            &array![coefficients[0] / 10., coefficients[1]],
+            1e-8,
+        );
+        // non-uniform error levels change the solution
+        assert!((&coefficients - &solve_tls(&y, &x)).mapv(f64::abs).sum() > 1e-6);
+    }
+
+    #[test]
+    fn test_recursive_least_squares_variances() {
+        let x = Array2::<f64>::random((2000, 2), Normal::new(0., 1.).unwrap());
+        let y =
+            x.dot(&array![1., -0.5]) + Array1::<f64>::random(2000, Normal::new(0., 0.5).unwrap());
+        let is_valid = vec![true; 2000];
+        let (coefficients, variances) =
+            solve_recursive_least_squares_with_variances(&y, &x, None, Some(1e6), None, &is_valid);
+        close_l2(
+            &coefficients,
+            &solve_recursive_least_squares(&y, &x, None, Some(1e6), None, &is_valid),
+            1e-12,
+        );
+        assert_eq!(variances.shape(), &[2000, 2]);
+        // with a diffuse prior, the expanding estimates approach the OLS variances
+        let residuals = &y - &x.dot(&coefficients.row(1999));
+        let ols_variances = ols_coef_covariance(&x, &residuals, 1998).diag().to_owned();
+        let last = variances.row(1999).to_owned();
+        assert!(((&last / &ols_variances) - 1.)
+            .mapv(f64::abs)
+            .iter()
+            .all(|r| *r < 0.05));
+        // the uncertainty shrinks as samples accumulate
+        assert!(variances[[100, 0]] > variances[[1999, 0]]);
+    }
+
+    #[test]
+    fn test_recursive_least_squares_reset() {
+        let x = Array2::<f64>::random((200, 2), Normal::new(0., 1.).unwrap());
+        let y_before = x.dot(&array![1., 2.]);
+        let y_after = x.dot(&array![-1., 0.5]);
+        let mut rls = RecursiveLeastSquares::new(2, 10.0, Some(50.), None);
+        for t in 0..200 {
+            rls.update(&x.row(t).to_owned(), y_before[t]);
+        }
+        // after a full reset the state matches a freshly constructed filter
+        rls.reset(10.0, Some(Array1::<f64>::zeros(2)));
+        let mut fresh = RecursiveLeastSquares::new(2, 10.0, Some(50.), None);
+        for t in 0..200 {
+            rls.update(&x.row(t).to_owned(), y_after[t]);
+            fresh.update(&x.row(t).to_owned(), y_after[t]);
+        }
+        close_l2(rls.coefficients(), fresh.coefficients(), 1e-10);
+        close_l2(rls.coefficients(), &array![-1., 0.5], 1e-2);
+
+        // without a mean, the current estimates are kept as the prior mean
+        let before = rls.coefficients().clone();
+        rls.reset(1e3, None);
+        close_l2(rls.coefficients(), &before, 1e-15);
+    }
+
+    #[test]
+    fn test_rolling_effective_df() {
+        let mut x = Array2::<f64>::random((300, 3), Normal::new(0., 1.).unwrap());
+        // make the features nearly collinear over the second half
+        for i in 150..300 {
+            let v = x[[i, 0]];
+            x[[i, 1]] = v + 1e-3 * x[[i, 1]];
+            x[[i, 2]] = -v + 1e-3 * x[[i, 2]];
+        }
+        let effective_df = rolling_effective_df(&x, 50, Some(10), 1.0);
+        assert!(effective_df.slice(s![..9]).iter().all(|v| v.is_nan()));
+        // matches the direct computation for a window
+        let window = x.slice(s![50..100, ..]).to_owned();
+        assert!((effective_df[99] - ridge_effective_df(&window, 1.0)).abs() < 1e-8);
+        // well-conditioned windows retain nearly all k degrees of freedom, collinear ones lose
+        // the directions without variation
+        assert!(effective_df[140] > 2.9 && effective_df[140] <= 3.);
+        assert!(effective_df[299] < 1.1);
+        // no penalty keeps all k degrees of freedom
+        let unpenalized = rolling_effective_df(&x, 50, None, 0.);
+        assert!(unpenalized
+            .slice(s![2..150])
+            .iter()
+            .all(|v| (v - 3.).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_solve_segmented() {
+        // slope 0.5 below the breakpoint at 1, slope 2 above it
+        let x = add_intercept(&Array2::from_shape_fn((100, 1), |(i, _)| {
+            i as f64 * 0.04 - 1.
+        }));
+        let y = x.column(1).mapv(|v| 0.3 + 0.5 * v + 1.5 * (v - 1.).max(0.));
+        let coefficients = solve_segmented(&y, &x, 1, 1.);
+        close_l2(&coefficients, &array![0.3, 0.5, 1.5], 1e-8);
+        // without a kink the hinge coefficient vanishes
+        let y = x.column(1).mapv(|v| 0.3 + 0.5 * v);
+        assert!(solve_segmented(&y, &x, 1, 0.5)[2].abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_rolling_ridge_matches_batch() {
+        let x = Array2::<f64>::random((120, 3), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1., -1., 0.5])
+            + Array1::<f64>::random(120, Normal::new(0., 0.3).unwrap());
+        let (window_size, min_periods, alpha) = (30, 5, 2.5);
+        for use_woodbury in [false, true] {
+            let coefficients = solve_rolling_ols(
+                &y,
+                &x,
+                window_size,
+                Some(min_periods),
+                Some(use_woodbury),
+                Some(alpha),
+                None,
+                None,
+                None,
+            );
+            for i in min_periods - 1..120 {
+                let start = (i + 1).saturating_sub(window_size);
+                let expected = solve_ridge(
+                    &y.slice(s![start..=i]).to_owned(),
+                    &x.slice(s![start..=i, ..]).to_owned(),
+                    alpha,
+                    None,
+                    None,
+                );
+                close_l2(&coefficients.row(i), &expected, 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_segmented_search() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (300, 1),
+            Normal::new(0., 2.).unwrap(),
+        ));
+        let y = x.column(1).mapv(|v| 1. - 0.5 * v + 2. * (v - 0.7).max(0.))
+            + Array1::<f64>::random(300, Normal::new(0., 0.2).unwrap());
+        let candidates: Vec<f64> = (0..41).map(|i| -2. + i as f64 * 0.1).collect();
+        let (breakpoint, coefficients) = solve_segmented_search(&y, &x, 1, &candidates);
+        assert!((breakpoint - 0.7).abs() <= 0.1 + 1e-12);
+        close_l2(
+            &coefficients,
+            &solve_segmented(&y, &x, 1, breakpoint),
+            1e-12,
+        );
+        assert!((coefficients[2] - 2.).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_solve_rolling_elastic_net() {
+        let x = Array2::<f64>::random((150, 4), Normal::new(0., 1.).unwrap());
+        // the second feature only matters over the second half
+        let mut y = x.dot(&array![1., 0., 0., 0.])
+            + Array1::<f64>::random(150, Normal::new(0., 0.1).unwrap());
+        for i in 75..150 {
+            y[i] += 2. * x[[i, 1]];
+        }
+        let coefficients = solve_rolling_elastic_net(
+            &y,
+            &x,
+            40,
+            Some(10),
+            0.05,
+            Some(1.),
+            None,
+            Some(1e-12),
+            None,
+        );
+        assert!(coefficients.slice(s![..9, ..]).iter().all(|c| c.is_nan()));
+        // each window matches a cold-started batch fit
+        for i in [9usize, 39, 80, 149] {
+            let start = (i + 1).saturating_sub(40);
+            let expected = solve_elastic_net(
+                &y.slice(s![start..=i]).to_owned(),
+                &x.slice(s![start..=i, ..]).to_owned(),
+                0.05,
+                Some(1.),
+                None,
+                Some(1e-12),
+                None,
+                None,
+                None,
+                None,
+            );
+            close_l2(&coefficients.row(i), &expected, 1e-6);
+        }
+        // the lasso tracks the entry of the second feature
+        assert!(coefficients[[60, 1]].abs() < 0.1);
+        assert!(coefficients[[149, 1]] > 1.5);
+        assert!(coefficients[[149, 3]].abs() < 0.1);
+    }
+
+    #[test]
+    fn test_ols_vs_gls_efficiency() {
+        let x = add_intercept(&Array2::from_shape_fn((60, 1), |(i, _)| i as f64 / 10.));
+        // spherical errors: OLS is BLUE
+        let efficiency = ols_vs_gls_efficiency(&x, &(Array2::<f64>::eye(60) * 2.));
+        close_l2(&efficiency, &array![1., 1.], 1e-8);
+        // AR(1) errors with strong autocorrelation: GLS is strictly more efficient
+        let omega =
+            Array2::from_shape_fn((60, 60), |(i, j)| 0.9f64.powi((i as i32 - j as i32).abs()));
+        let efficiency = ols_vs_gls_efficiency(&x, &omega);
+        assert!(efficiency.iter().all(|e| *e > 1.));
+        // heteroskedastic errors growing with the trend
+        let omega = Array2::from_diag(&x.column(1).mapv(|v| 0.1 + v * v));
+        assert!(ols_vs_gls_efficiency(&x, &omega).iter().all(|e| *e > 1.));
+    }
+
+    #[test]
+    fn test_solve_min_norm_interpolate() {
+        let x = Array2::<f64>::random((20, 200), Normal::new(0., 1.).unwrap());
+        let y = Array1::<f64>::random(20, Normal::new(0., 1.).unwrap());
+        let coefficients = solve_min_norm_interpolate(&y, &x);
+        close_l2(&x.dot(&coefficients), &y, 1e-10);
+        // it matches the pseudo-inverse solution
+        close_l2(
+            &coefficients,
+            &solve_ols(&y, &x, Some(SolveMethod::SVD), None),
+            1e-8,
+        );
+        // adding any null space direction keeps interpolating but increases the norm
+        let z = Array1::<f64>::random(200, Normal::new(0., 1.).unwrap());
+        let projection = solve_min_norm_interpolate(&x.dot(&z), &x); // onto the row space
+        let other = &coefficients + &(&z - &projection);
+        close_l2(&x.dot(&other), &y, 1e-8);
+        assert!(other.dot(&other) > coefficients.dot(&coefficients));
+    }
+
+    #[test]
+    fn test_elastic_net_path_default_grid() {
+        let x = Array2::<f64>::random((100, 4), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1., -2., 0., 0.5]);
+        let path =
+            solve_elastic_net_path(&y, &x, &Array1::zeros(0), Some(1.), None, None, None, None);
+        assert_eq!(path.shape(), &[100, 4]);
+        // everything is zero at alpha_max, and the coefficients approach OLS at the small end
+        assert!(path.row(0).iter().all(|w| w.abs() < 1e-10));
+        assert!(path.row(1).iter().any(|w| *w != 0.));
+        close_l2(&path.row(99), &array![1., -2., 0., 0.5], 0.05);
+
+        // the rows follow the decreasing default grid, whatever the order of explicit alphas
+        let alphas = default_alpha_grid(&y, &x, 1.);
+        assert_eq!(alphas.len(), 100);
+        assert!(alphas.windows(2).into_iter().all(|w| w[0] > w[1]));
+        let ascending = alphas.slice(s![..;-1]).to_owned();
+        let explicit = solve_elastic_net_path(&y, &x, &ascending, Some(1.), None, None, None, None);
+        close_l2(&explicit, &path, 1e-12);
+    }
+
+    #[test]
+    fn test_solve_nnls() {
+        let x = Array2::<f64>::random((500, 4), Normal::new(0., 1.).unwrap());
+        let noise = Array1::<f64>::random(500, Normal::new(0., 0.1).unwrap());
+        // a positive solution is unaffected by the constraint
+        let y = x.dot(&array![1., 0.5, 2., 0.25]) + &noise;
+        close_l2(
+            &solve_nnls(&y, &x, None, None),
+            &solve_ols(&y, &x, None, None),
+            1e-8,
+        );
+
+        let y = x.dot(&array![1., -2., 0.5, -0.5]) + &noise;
+        let coefficients = solve_nnls(&y, &x, None, None);
+        assert!(coefficients.iter().all(|w| *w >= 0.));
+        // KKT: zero gradient on the positive coefficients, non-positive on the zeros
+        let gradient = x.t().dot(&(&y - &x.dot(&coefficients)));
+        for (w, g) in coefficients.iter().zip(gradient.iter()) {
+            if *w > 0. {
+                assert!(g.abs() < 1e-6);
+            } else {
+                assert!(*g <= 1e-6);
+            }
+        }
+        assert_eq!(coefficients[1], 0.);
+        assert!(coefficients[0] > 0.5);
+    }
+
+    #[test]
+    fn test_fused_rolling_ridge() {
+        let n = 500;
+        let x = Array2::<f64>::random((n, 2), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1., -1.]) + Array1::<f64>::random(n, Normal::new(0., 1.).unwrap());
+        let path_variation = |temporal_alpha: f64| {
+            let coefficients = solve_rolling_ols(
+                &y,
+                &x,
+                30,
+                Some(30),
+                None,
+                Some(0.1),
+                None,
+                None,
+                Some(temporal_alpha),
+            );
+            let coefficients = coefficients.slice(s![29.., ..]);
+            let changes = &coefficients.slice(s![1.., ..]) - &coefficients.slice(s![..-1, ..]);
+            changes.mapv(|c| c * c).mean().unwrap()
+        };
+        // fusing towards the previous window smooths the coefficient path
+        let variations = [0., 10., 100.].map(path_variation);
+        assert!(variations[1] < variations[0]);
+        assert!(variations[2] < variations[1]);
+
+        // a zero temporal penalty reproduces the independent rolling ridge fits
+        let independent =
+            solve_rolling_ols(&y, &x, 30, Some(30), None, Some(0.1), None, None, None);
+        let fused = solve_fused_rolling_ridge(&y, &x, 30, Some(30), 0.1, 0.);
+        close_l2(
+            &fused.slice(s![29.., ..]),
+            &independent.slice(s![29.., ..]),
+            1e-8,
+        );
+        assert!(fused.slice(s![..29, ..]).iter().all(|c| c.is_nan()));
+    }
+
+    #[test]
+    fn test_overall_significance_robust() {
+        let n = 2_000;
+        let x = add_intercept(&Array2::<f64>::random((n, 3), Normal::new(0., 1.).unwrap()));
+        let y = x.dot(&array![1., 0.2, 0., 0.1])
+            + Array1::<f64>::random(n, Normal::new(0., 1.).unwrap());
+        let coefficients = solve_ols(&y, &x, None, None);
+        let residuals = &y - &x.dot(&coefficients);
+
+        // with the classical covariance the Wald statistic is q times the overall F statistic
+        let rss = residuals.dot(&residuals);
+        let deviations = &y - y.mean().unwrap();
+        let f_statistic = ((deviations.dot(&deviations) - rss) / 3.) / (rss / (n - 4) as f64);
+        let classical = ols_coef_covariance(&x, &residuals, n - 4);
+        let (chi2, df, _) = overall_significance_robust(&coefficients, &classical, Some(0));
+        assert_eq!(df, 3);
+        assert!((chi2 - 3. * f_statistic).abs() < 1e-8 * chi2);
+
+        // under homoskedasticity the robust version agrees closely
+        let robust = ols_robust_covariance(&x, &residuals, CovarianceType::HC3);
+        let (chi2_robust, _, p_value) =
+            overall_significance_robust(&coefficients, &robust, Some(0));
+        assert!((chi2_robust / chi2 - 1.).abs() < 0.2);
+        assert!(p_value < 1e-6);
+    }
+
+    #[test]
+    fn test_ridge_per_feature_alphas() {
+        let x = Array2::<f64>::random((300, 3), Normal::new(0., 1.).unwrap());
+        let y =
+            x.dot(&array![1., 2., -1.]) + Array1::<f64>::random(300, Normal::new(0., 0.1).unwrap());
+        // uniform per-feature penalties match the scalar penalty
+        for method in [SolveMethod::Cholesky, SolveMethod::SVD] {
+            close_l2(
+                &solve_ridge(&y, &x, array![5., 5., 5.], Some(method), None),
+                &solve_ridge(&y, &x, 5., Some(SolveMethod::Cholesky), None),
+                1e-10,
+            );
+        }
+        // an unpenalized feature next to a heavily shrunk one
+        let coefficients = solve_ridge(&y, &x, array![0., 1e12, 0.], None, None);
+        assert!(coefficients[1].abs() < 1e-6);
+        let reduced = solve_ols(&y, &x.select(Axis(1), &[0, 2]), None, None);
+        close_l2(&coefficients.select(Axis(0), &[0, 2]), &reduced, 1e-6);
+        // generalized Tikhonov: X^T (y - X B) = diag(alphas) B
+        let alphas = array![0.5, 50., 0.];
+        let coefficients = solve_ridge(&y, &x, alphas.clone(), Some(SolveMethod::SVD), None);
+        close_l2(
+            &x.t().dot(&(&y - &x.dot(&coefficients))),
+            &(&alphas * &coefficients),
+            1e-8,
+        );
+    }
+
+    #[test]
+    fn test_ridge_gcv() {
+        let x = Array2::<f64>::random((100, 20), Normal::new(0., 1.).unwrap());
+        let beta = Array1::<f64>::random(20, Normal::new(0., 0.3).unwrap());
+        let y = x.dot(&beta) + Array1::<f64>::random(100, Normal::new(0., 1.).unwrap());
+        let alphas = Array1::logspace(10., -3., 4., 50);
+        let (alpha, coefficients) = ridge_gcv(&y, &x, &alphas);
+        close_l2(
+            &coefficients,
+            &solve_ridge(&y, &x, alpha, Some(SolveMethod::SVD), None),
+            1e-10,
+        );
+        // the analytic score matches a brute force evaluation of every alpha
+        let n = 100.;
+        let gcv = |alpha: f64| {
+            let residuals = &y - &x.dot(&solve_ridge(&y, &x, alpha, None, None));
+            n * residuals.dot(&residuals) / (n - ridge_hat_trace(&x, alpha)).powi(2)
+        };
+        let brute_force = alphas
+            .iter()
+            .copied()
+            .min_by(|a, b| gcv(*a).total_cmp(&gcv(*b)))
+            .unwrap();
+        assert!((gcv(alpha) - gcv(brute_force)).abs() < 1e-10 * gcv(alpha));
+        // noisy data with small coefficients calls for a non-trivial penalty (about 1 / 0.3^2)
+        assert!(alpha > 0.1 && alpha < 1e3);
+    }
+
+    #[test]
+    fn test_robust_estimator_se() {
+        let n = 20_000;
+        let x = Array2::<f64>::random((n, 2), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1., -0.5]) + Array1::<f64>::random(n, Normal::new(0., 1.).unwrap());
+        let coefficients = solve_huber(&y, &x, 1.345, None, None, None);
+        let se = robust_estimator_se(&y, &x, &coefficients, PsiFunction::Huber(1.345), None);
+        // Huber's c = 1.345 is 95% efficient under normal errors: var = 1 / (0.95 n)
+        let expected = (1. / (0.95 * n as f64)).sqrt();
+        assert!(se.iter().all(|se| (se / expected - 1.).abs() < 0.05));
+
+        // a huge threshold recovers the (asymptotic, HC0) OLS sandwich with a known scale
+        let ols = solve_ols(&y, &x, None, None);
+        let residuals = &y - &x.dot(&ols);
+        let se = robust_estimator_se(&y, &x, &ols, PsiFunction::Huber(1e10), Some(1.));
+        let hc0 = ols_robust_covariance(&x, &residuals, CovarianceType::HC0)
+            .diag()
+            .mapv(f64::sqrt);
+        close_l2(&se, &hc0, 1e-10);
+
+        // Tukey's bisquare with c = 4.685 is also 95% efficient
+        let se = robust_estimator_se(&y, &x, &coefficients, PsiFunction::Tukey(4.685), None);
+        assert!(se.iter().all(|se| (se / expected - 1.).abs() < 0.05));
+    }
+
+    #[test]
+    fn test_tikhonov_gcv() {
+        // piecewise-linear (hat function) basis on 30 knots with a second difference penalty
+        let (n, k) = (200, 30);
+        let t = Array1::linspace(0., 1., n);
+        let x = Array2::from_shape_fn((n, k), |(i, j)| {
+            (1. - (t[i] * (k - 1) as f64 - j as f64).abs()).max(0.)
+        });
+        let gamma = Array2::from_shape_fn((k - 2, k), |(i, j)| match j as isize - i as isize {
+            0 | 2 => 1.,
+            1 => -2.,
+            _ => 0.,
+        });
+        let signal = t.mapv(|t| (2. * std::f64::consts::PI * t).sin());
+        let y = &signal + &Array1::<f64>::random(n, Normal::new(0., 0.3).unwrap());
+        let alphas = Array1::logspace(10., -6., 6., 61);
+        let (alpha, coefficients) = tikhonov_gcv(&y, &x, &gamma, &alphas);
+
+        let penalized = x.t().dot(&x) + gamma.t().dot(&gamma) * alpha;
+        let expected = inv(&penalized, false, None).dot(&x.t().dot(&y));
+        close_l2(&coefficients, &expected, 1e-6);
+
+        // neither interpolating the noise nor flattening the sine to a line
+        let error = |coefficients: &Array1<f64>| {
+            let deviations = &x.dot(coefficients) - &signal;
+            deviations.dot(&deviations) / n as f64
+        };
+        let fit = |alpha: f64| {
+            let penalized = x.t().dot(&x) + gamma.t().dot(&gamma) * alpha;
+            inv(&penalized, false, None).dot(&x.t().dot(&y))
+        };
+        assert!(alpha > 1e-6 && alpha < 1e6);
+        assert!(error(&coefficients) < error(&fit(1e-6)));
+        assert!(error(&coefficients) < error(&fit(1e6)));
+    }
+
+    #[test]
+    fn test_ols_loocv() {
+        let x = Array2::<f64>::random((60, 3), Normal::new(0., 1.).unwrap());
+        let y =
+            x.dot(&array![1., 2., -1.]) + Array1::<f64>::random(60, Normal::new(0., 1.).unwrap());
+        close_l2(&leverage_qr(&x), &leverage(&x), 1e-10);
+
+        // the closed form matches explicitly refitting without each observation
+        let mut press = 0.;
+        for i in 0..60 {
+            let keep: Vec<usize> = (0..60).filter(|&j| j != i).collect();
+            let coefficients = solve_ols(
+                &y.select(Axis(0), &keep),
+                &x.select(Axis(0), &keep),
+                None,
+                None,
+            );
+            press += (y[i] - x.row(i).dot(&coefficients)).powi(2);
+        }
+        assert!((ols_loocv(&y, &x, None) - press).abs() < 1e-8 * press);
+        assert!((ols_loocv(&y, &x, Some(SolveMethod::SVD)) - press).abs() < 1e-8 * press);
+    }
+
+    #[test]
+    fn test_design_condition_number_and_rank() {
+        let x = Array2::<f64>::random((100, 3), Normal::new(0., 1.).unwrap());
+        let s = singular_values(&x);
+        assert!((design_condition_number(&x) - s[0] / s[2]).abs() < 1e-10);
+        assert!(
+            (design_condition_number(&x).powi(2) - ridge_condition_number(&x, 0.)).abs() < 1e-8
+        );
+        assert_eq!(design_rank(&x, None), 3);
+
+        // an exactly collinear column drops the rank, a nearly collinear one explodes cond(X)
+        let mut collinear = Array2::<f64>::zeros((100, 4));
+        collinear.slice_mut(s![.., ..3]).assign(&x);
+        collinear
+            .column_mut(3)
+            .assign(&(&x.column(0) + &x.column(1)));
+        assert_eq!(design_rank(&collinear, None), 3);
+        assert!(design_condition_number(&collinear) > 1e12);
+        collinear.column_mut(3).mapv_inplace(|v| v + 1e-4);
+        assert_eq!(design_rank(&collinear, None), 4);
+        assert_eq!(design_rank(&collinear, Some(1e-3)), 3);
+        assert!(design_condition_number(&x.t().to_owned()).is_infinite());
+    }
+
+    #[test]
+    fn test_qr_replace_column() {
+        let x = Array2::<f64>::random((200, 5), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1., -1., 0.5, 2., 0.])
+            + Array1::<f64>::random(200, Normal::new(0., 1.).unwrap());
+        let qr = compute_qr(&x);
+        close_l2(&qr.solve(&y), &solve_ols(&y, &x, None, None), 1e-10);
+
+        // permute each column in turn, as for permutation feature importance
+        for column in 0..5 {
+            let mut permuted = x.column(column).to_vec();
+            permuted.reverse();
+            let permuted = Array1::from(permuted);
+            let updated = qr.replace_column(column, &permuted);
+            let mut x_permuted = x.clone();
+            x_permuted.column_mut(column).assign(&permuted);
+            close_l2(
+                &updated.solve(&y),
+                &solve_ols(&y, &x_permuted, None, None),
+                1e-8,
+            );
+            // still a valid factorization: orthonormal Q, upper triangular R and X P = Q R
+            close_l2(&updated.q.t().dot(&updated.q), &Array2::eye(5), 1e-10);
+            for i in 1..5 {
+                assert!(updated
+                    .r
+                    .row(i)
+                    .slice(s![..i])
+                    .iter()
+                    .all(|r| r.abs() < 1e-10));
+            }
+            let reconstructed = updated.q.dot(&updated.r);
+            for (j, &p) in updated.permutation.iter().enumerate() {
+                close_l2(&reconstructed.column(j), &x_permuted.column(p), 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mad_standardized_residuals() {
+        let mut residuals = Array1::<f64>::random(1_000, Normal::new(0., 2.).unwrap());
+        for i in [10, 500, 900] {
+            residuals[i] = 50.;
+        }
+        let standardized = mad_standardized_residuals(&residuals);
+        let flagged: Vec<usize> = (0..1_000).filter(|&i| standardized[i].abs() > 3.).collect();
+        for i in [10, 500, 900] {
+            assert!(flagged.contains(&i));
+        }
+        // a few clean normal residuals exceed 3 sigma by chance (0.27% in expectation)
+        assert!(flagged.len() < 15);
+        // the outliers do not inflate the robust scale, which stays close to the true one
+        let scale = (residuals[0] - residuals[1]) / (standardized[0] - standardized[1]);
+        assert!((scale / 2. - 1.).abs() < 0.15);
+    }
+
+    #[test]
+    fn test_variance_inflation_factors() {
+        let n = 500;
+        let x = Array2::<f64>::random((n, 3), Normal::new(0., 1.).unwrap());
+        let mut features = add_intercept(&x);
+        // the third feature is correlated with the first
+        let correlated = &x.column(0) + &(&x.column(2) * 0.5);
+        features.column_mut(3).assign(&correlated);
+        let vifs = variance_inflation_factors(&features);
+        assert!(vifs[0].is_nan());
+        // matches 1 / (1 - R^2) from the auxiliary regressions (with an intercept)
+        for j in 1..4 {
+            let others: Vec<usize> = (0..4).filter(|&l| l != j).collect();
+            let fit = solve_ols_with_stats(
+                &features.column(j).to_owned(),
+                &features.select(Axis(1), &others),
+                None,
+                None,
+            );
+            assert!((vifs[j] - 1. / (1. - fit.r_squared)).abs() < 1e-8 * vifs[j]);
+        }
+        assert!(vifs[1] > 4. && vifs[2] < 1.1);
+
+        // a perfectly collinear column makes all the columns involved infinite
+        let mut collinear = Array2::<f64>::zeros((n, 4));
+        collinear.slice_mut(s![.., ..3]).assign(&x);
+        collinear
+            .column_mut(3)
+            .assign(&(&x.column(0) - &x.column(1)));
+        let vifs = variance_inflation_factors(&collinear);
+        assert!(vifs[0].is_infinite() && vifs[1].is_infinite() && vifs[3].is_infinite());
+        assert!(vifs[2].is_finite() && vifs[2] < 1.1);
+    }
+
+    #[test]
+    fn test_drop_invalid_rows() {
+        let x = Array2::<f64>::random((100, 2), Normal::new(0., 1.).unwrap());
+        let y = x.dot(&array![1., 2.]) + Array1::<f64>::random(100, Normal::new(0., 0.1).unwrap());
+        let expected = solve_ols(&y, &x, None, None);
+
+        let (mut y_dirty, mut x_dirty) = (y.clone(), x.clone());
+        y_dirty[3] = f64::NAN;
+        x_dirty[[50, 1]] = f64::NAN;
+        x_dirty[[99, 0]] = f64::INFINITY;
+
+        let (y_valid, x_valid, is_valid) = drop_invalid_rows(&y_dirty, &x_dirty);
+        assert_eq!(y_valid.len(), 97);
+        assert_eq!(is_valid.iter().filter(|v| !**v).count(), 3);
+        assert!(!is_valid[3] && !is_valid[50] && !is_valid[99]);
+        // the dropped rows barely move the coefficients of this well-specified model
+        let coefficients = solve_ols(&y_valid, &x_valid, None, None);
+        close_l2(&coefficients, &expected, 0.05);
+        let rows: Vec<usize> = (0..100).filter(|&i| is_valid[i]).collect();
+        close_l2(
+            &coefficients,
+            &solve_ols(
+                &y.select(Axis(0), &rows),
+                &x.select(Axis(0), &rows),
+                None,
+                None,
+            ),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn test_incremental_pca() {
+        let n = 5_000;
+        // stationary data with well separated leading variances, rotated away from the axes
+        let z = Array2::<f64>::random((n, 5), Normal::new(0., 1.).unwrap())
+            * &array![3., 2., 0.5, 0.3, 0.2];
+        let rotation = compute_qr(&Array2::<f64>::random((5, 5), Normal::new(0., 1.).unwrap())).q;
+        let x = z.dot(&rotation.t()) + 1.;
+        let y = x.dot(&array![1., -1., 0.5, 0., 2.])
+            + Array1::<f64>::random(n, Normal::new(0., 0.1).unwrap());
+
+        let mut pca = IncrementalPca::new(5, 2);
+        for i in 0..n {
+            pca.update(&x.row(i).to_owned());
+        }
+        assert_eq!(pca.count(), n);
+        close_l2(pca.mean(), &x.mean_axis(Axis(0)).unwrap(), 1e-10);
+
+        // the tracked components converge to those of the batch PCA (up to sign)
+        let centered = &x - &x.mean_axis(Axis(0)).unwrap();
+        let batch_variances = gram_eigenvalues(&centered) / (n - 1) as f64;
+        for j in 0..2 {
+            let component = pca.components().row(j);
+            // a batch principal axis v satisfies X^T X v = s^2 v
+            let image = centered.t().dot(&centered.dot(&component));
+            let cosine = image.dot(&component) / image.dot(&image).sqrt();
+            assert!(cosine > 0.999, "component {j}: cosine {cosine}");
+            let variance = pca.explained_variance()[j];
+            assert!((variance / batch_variances[j] - 1.).abs() < 0.05);
+        }
+        let scores = pca.transform(&x.row(0).to_owned());
+        close_l2(&scores, &pca.components().dot(&centered.row(0)), 1e-10);
+
+        // streaming PCR agrees with the batch fit on the same two components
+        close_l2(
+            &solve_pcr_incremental(&y, &x, &pca),
+            &solve_pcr(&y, &x, 2, None),
+            0.05,
+        );
+    }
+
+    #[test]
+    fn test_gls_coef_covariance() {
+        let x = add_intercept(&Array2::<f64>::random(
+            (80, 2),
+            Normal::new(0., 1.).unwrap(),
+        ));
+        // spherical errors: the OLS covariance sigma^2 inv(X^T X)
+        let covariance = gls_coef_covariance(&x, &(Array2::<f64>::eye(80) * 2.5));
+        close_l2(&covariance, &(inv(&x.t().dot(&x), true, None) * 2.5), 1e-10);
+        // the OLS form with sigma^2 = RSS / dof, for residuals whose RSS / dof is 2.5
+        let residuals = Array1::from_elem(80, (2.5f64 * 77. / 80.).sqrt());
+        close_l2(&covariance, &ols_coef_covariance(&x, &residuals, 77), 1e-10);
+
+        // AR(1) errors: matches the explicit inverse, and is the GLS variance of the efficiency
+        let omega =
+            Array2::from_shape_fn((80, 80), |(i, j)| 0.7f64.powi((i as i32 - j as i32).abs()));
+        let covariance = gls_coef_covariance(&x, &omega);
+        let explicit = inv(&x.t().dot(&inv(&omega, false, None)).dot(&x), false, None);
+        close_l2(&covariance, &explicit, 1e-8);
+        let xtx_inv = inv(&x.t().dot(&x), true, None);
+        let ols_covariance = xtx_inv.dot(&x.t().dot(&omega).dot(&x)).dot(&xtx_inv);
+        close_l2(
+            &(ols_covariance.diag().to_owned() / covariance.diag()),
+            &ols_vs_gls_efficiency(&x, &omega),
+            1e-8,
         );
-        assert_close_l2!(&xtx_inv, &expected, 0.00001);
     }
 }
 